@@ -0,0 +1,178 @@
+#![allow(dead_code)]
+use mogrify::{AsyncMogrify, MogrificationError, Mogrify};
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{LazyLock, Mutex};
+use std::task::{Context, Poll, Waker};
+
+// An in-memory stand-in for the database, recording the order lookups start and finish in
+struct AccountStore {
+    accounts: HashSet<String>,
+    events: Mutex<Vec<String>>,
+}
+
+static STORE: LazyLock<AccountStore> = LazyLock::new(|| AccountStore {
+    accounts: HashSet::from(["alice", "bob", "carol"].map(String::from)),
+    events: Mutex::new(Vec::new()),
+});
+
+// Pending once before completing, like a query waiting on the network
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            Poll::Ready(())
+        } else {
+            yielded = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    })
+    .await
+}
+
+struct AccountId(String);
+
+async fn existing_account(id: String) -> Result<AccountId, MogrificationError> {
+    STORE.events.lock().unwrap().push(format!("start {id}"));
+    yield_now().await;
+    STORE.events.lock().unwrap().push(format!("end {id}"));
+    if STORE.accounts.contains(&id) {
+        Ok(AccountId(id))
+    } else {
+        Err(MogrificationError::new("unknown account"))
+    }
+}
+
+async fn not_blank(name: String) -> Result<String, MogrificationError> {
+    if name.trim().is_empty() {
+        Err(MogrificationError::new("must not be blank"))
+    } else {
+        Ok(name)
+    }
+}
+
+struct RawPayee {
+    account: String,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawPayee, async)]
+struct Payee {
+    #[mogrify(parse = existing_account)]
+    account: AccountId,
+}
+
+struct RawTransfer {
+    from_account: String,
+    to_account: String,
+    watchers: Vec<String>,
+    memo: Option<String>,
+    amount: u64,
+    payee: RawPayee,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawTransfer, async)]
+struct Transfer {
+    #[mogrify(parse = existing_account)]
+    from_account: AccountId,
+    #[mogrify(parse = existing_account)]
+    to_account: AccountId,
+    #[mogrify(parse = existing_account)]
+    watchers: Vec<AccountId>,
+    #[mogrify(and_then = not_blank)]
+    memo: Option<String>,
+    amount: u64,
+    // nested async conversions are dispatched explicitly
+    #[mogrify(parse = Payee::mogrify_from)]
+    payee: Payee,
+}
+
+struct RawBatch<R> {
+    items: Vec<R>,
+    memo: Option<String>,
+}
+
+// generic params are bound `Send` too, so the future stays `Send`
+#[derive(Mogrify)]
+#[mogrify(for<R> RawBatch<R>, async)]
+struct Batch<T> {
+    items: Vec<T>,
+    #[mogrify(and_then = not_blank)]
+    memo: Option<String>,
+}
+
+// A minimal executor, since the stub never actually waits on anything
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+fn main() {
+    let raw = RawTransfer {
+        from_account: "alice".to_string(),
+        to_account: "bob".to_string(),
+        watchers: vec![],
+        memo: Some("rent".to_string()),
+        amount: 10,
+        payee: RawPayee {
+            account: "carol".to_string(),
+        },
+    };
+    // the future is `Send`, so it can be awaited on another thread
+    let future = Transfer::mogrify_from(raw);
+    let transfer = std::thread::spawn(move || block_on(future))
+        .join()
+        .unwrap()
+        .expect("successful conversion");
+    assert_eq!(transfer.to_account.0, "bob");
+    assert_eq!(transfer.payee.account.0, "carol");
+    // every lookup was in flight at the same time
+    assert_eq!(
+        *STORE.events.lock().unwrap(),
+        vec![
+            "start alice",
+            "start bob",
+            "start carol",
+            "end alice",
+            "end bob",
+            "end carol"
+        ]
+    );
+
+    let raw = RawTransfer {
+        from_account: "mallory".to_string(),
+        to_account: "bob".to_string(),
+        watchers: vec!["bob".to_string(), "eve".to_string()],
+        memo: Some(" ".to_string()),
+        amount: 10,
+        payee: RawPayee {
+            account: "eve".to_string(),
+        },
+    };
+    let future = Batch::<u8>::mogrify_from(RawBatch {
+        items: vec![1i64, 2],
+        memo: None,
+    });
+    let batch = std::thread::spawn(move || block_on(future))
+        .join()
+        .unwrap()
+        .expect("successful conversion");
+    assert_eq!(batch.items, vec![1, 2]);
+
+    let err = block_on(Transfer::mogrify_from(raw))
+        .err()
+        .expect("failed conversion");
+    let paths = err.failures().iter().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![".from_account", ".watchers[1]", ".memo", ".payee.account"]
+    );
+}
@@ -0,0 +1,75 @@
+#![allow(dead_code)]
+use mogrify::{FailureCode, MogrificationError, Mogrify};
+use std::collections::HashMap;
+
+struct RawSignup {
+    email: Option<String>,
+    age: String,
+    referrals: HashMap<i64, u32>,
+    username: String,
+    plan: String,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawSignup)]
+struct Signup {
+    #[mogrify(require)]
+    email: String,
+    #[mogrify(range(min = 13), parse)]
+    age: u8,
+    referrals: HashMap<u8, u32>,
+    #[mogrify(and_then = available)]
+    username: String,
+    plan: String,
+}
+
+fn available(username: String) -> Result<String, MogrificationError> {
+    if username == "admin" {
+        Err(MogrificationError::new_coded(
+            FailureCode::Custom("taken".to_string()),
+            "username is taken",
+        ))
+    } else {
+        Ok(username)
+    }
+}
+
+fn main() {
+    let raw = RawSignup {
+        email: None,
+        age: "old".to_string(),
+        referrals: HashMap::from([(300, 1)]),
+        username: "admin".to_string(),
+        plan: "free".to_string(),
+    };
+    let err = Signup::try_from(raw).err().expect("failed conversion");
+    let codes = err
+        .failures()
+        .iter()
+        .map(|f| (f.path(), f.code().to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        codes,
+        vec![
+            (".email".to_string(), "required".to_string()),
+            (".age".to_string(), "parse".to_string()),
+            (".referrals[\"300\"]".to_string(), "invalid_key".to_string()),
+            (".username".to_string(), "taken".to_string()),
+        ]
+    );
+    assert!(err.has_code(&FailureCode::Required));
+    let taken = err
+        .failures_with_code(&FailureCode::Custom("taken".to_string()))
+        .count();
+    assert_eq!(taken, 1);
+
+    let raw = RawSignup {
+        email: Some("a@b.c".to_string()),
+        age: "9".to_string(),
+        referrals: HashMap::new(),
+        username: "ferris".to_string(),
+        plan: "free".to_string(),
+    };
+    let err = Signup::try_from(raw).err().expect("failed conversion");
+    assert_eq!(err.failures()[0].code(), &FailureCode::OutOfRange);
+}
@@ -0,0 +1,87 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+use std::collections::{BTreeMap, BTreeSet, HashSet, LinkedList, VecDeque};
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Positive(i64);
+impl TryFrom<i64> for Positive {
+    type Error = MogrificationError;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value > 0 {
+            Ok(Positive(value))
+        } else {
+            Err(MogrificationError::new("must be positive"))
+        }
+    }
+}
+impl From<Positive> for i64 {
+    fn from(value: Positive) -> Self {
+        value.0
+    }
+}
+
+struct RawInput {
+    boxed: Box<i64>,
+    name: Box<str>,
+    ids: Box<[i64]>,
+    queue: VecDeque<i64>,
+    list: LinkedList<i64>,
+    unique: HashSet<i64>,
+    sorted: BTreeSet<i64>,
+    lookup: BTreeMap<String, i64>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawInput, reverse)]
+struct Input {
+    boxed: Box<Positive>,
+    // unsized boxes are converted whole
+    name: Box<str>,
+    ids: Box<[i64]>,
+    queue: VecDeque<Positive>,
+    list: LinkedList<Positive>,
+    unique: HashSet<Positive>,
+    sorted: BTreeSet<Positive>,
+    lookup: BTreeMap<String, Positive>,
+}
+
+fn main() {
+    let raw = RawInput {
+        boxed: Box::new(1),
+        name: "name".into(),
+        ids: Box::new([1, 2]),
+        queue: VecDeque::from([1, 2]),
+        list: LinkedList::from([1, 2]),
+        unique: HashSet::from([1, 2]),
+        sorted: BTreeSet::from([1, 2]),
+        lookup: BTreeMap::from([("a".to_string(), 1)]),
+    };
+    let input: Input = raw.try_into().expect("successful conversion");
+    let raw: RawInput = input.into();
+    assert_eq!(*raw.boxed, 1);
+    assert_eq!(&*raw.name, "name");
+    assert_eq!(*raw.ids, [1, 2]);
+
+    let raw = RawInput {
+        boxed: Box::new(0),
+        name: "name".into(),
+        ids: Box::new([]),
+        queue: VecDeque::from([1, -2]),
+        list: LinkedList::from([-1, 2]),
+        unique: HashSet::from([1]),
+        sorted: BTreeSet::from([3, -4, 5]),
+        lookup: BTreeMap::from([("a".to_string(), 1), ("b".to_string(), -1)]),
+    };
+    let err = Input::try_from(raw).err().expect("failed conversion");
+    let paths = err.failures().iter().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            ".boxed",
+            ".queue[1]",
+            ".list[0]",
+            ".sorted[0]",
+            ".lookup[\"b\"]"
+        ]
+    );
+}
@@ -0,0 +1,128 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify, TryFromWith};
+
+// Request-scoped data the conversion rules depend on
+struct Tenant {
+    max_quantity: u32,
+    countries: Vec<&'static str>,
+    today: u32,
+}
+
+struct RawAddress {
+    street: String,
+    country: String,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawAddress, context = Tenant)]
+struct Address {
+    street: String,
+    #[mogrify(and_then = supported_country)]
+    country: String,
+}
+
+fn supported_country(country: String, tenant: &Tenant) -> Result<String, MogrificationError> {
+    if tenant.countries.contains(&country.as_str()) {
+        Ok(country)
+    } else {
+        Err(MogrificationError::new("country not supported"))
+    }
+}
+
+struct RawOrder {
+    quantity: String,
+    deliver_on: u32,
+    shipping: Option<RawAddress>,
+    billing: Vec<RawAddress>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawOrder, context = Tenant, validate = check_order)]
+struct Order {
+    // `parse` and `and_then` functions may ignore the context
+    #[mogrify(parse = mogrify::util::force_parse, and_then = within_limit)]
+    quantity: u32,
+    deliver_on: u32,
+    // nested conversions receive the same context
+    #[mogrify(require, context)]
+    shipping: Address,
+    #[mogrify(context)]
+    billing: Vec<Address>,
+}
+
+fn within_limit(quantity: u32, tenant: &Tenant) -> Result<u32, MogrificationError> {
+    if quantity <= tenant.max_quantity {
+        Ok(quantity)
+    } else {
+        Err(MogrificationError::new("quantity over limit"))
+    }
+}
+
+fn check_order(order: &Order, tenant: &Tenant) -> Result<(), MogrificationError> {
+    if order.deliver_on < tenant.today {
+        Err(MogrificationError::new("delivery date has passed"))
+    } else {
+        Ok(())
+    }
+}
+
+fn main() {
+    let tenant = Tenant {
+        max_quantity: 10,
+        countries: vec!["NZ", "AU"],
+        today: 100,
+    };
+
+    let raw = RawOrder {
+        quantity: "3".to_string(),
+        deliver_on: 101,
+        shipping: Some(RawAddress {
+            street: "1 Queen St".to_string(),
+            country: "NZ".to_string(),
+        }),
+        billing: vec![],
+    };
+    let order = Order::try_from_with(raw, &tenant).expect("successful conversion");
+    assert_eq!(order.shipping.country, "NZ");
+
+    let raw = RawOrder {
+        quantity: "30".to_string(),
+        deliver_on: 101,
+        shipping: Some(RawAddress {
+            street: "1 Queen St".to_string(),
+            country: "US".to_string(),
+        }),
+        billing: vec![
+            RawAddress {
+                street: "2 King St".to_string(),
+                country: "AU".to_string(),
+            },
+            RawAddress {
+                street: "3 Main St".to_string(),
+                country: "UK".to_string(),
+            },
+        ],
+    };
+    let err = Order::try_from_with(raw, &tenant)
+        .err()
+        .expect("failed conversion");
+    let paths = err.failures().iter().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![".quantity", ".shipping.country", ".billing[1].country"]
+    );
+
+    let raw = RawOrder {
+        quantity: "3".to_string(),
+        deliver_on: 99,
+        shipping: Some(RawAddress {
+            street: "1 Queen St".to_string(),
+            country: "AU".to_string(),
+        }),
+        billing: vec![],
+    };
+    let err = Order::try_from_with(raw, &tenant)
+        .err()
+        .expect("failed validation");
+    assert_eq!(err.failures()[0].message(), "delivery date has passed");
+}
@@ -0,0 +1,86 @@
+#![allow(dead_code)]
+use mogrify::Mogrify;
+
+// shaped like the enums prost generates
+#[derive(Clone, Copy)]
+#[repr(i32)]
+enum ProtoStatus {
+    Unspecified = 0,
+    Active = 1,
+    Disabled = 2,
+}
+
+#[derive(Debug)]
+struct UnknownEnumValue(i32);
+
+impl TryFrom<i32> for ProtoStatus {
+    type Error = UnknownEnumValue;
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ProtoStatus::Unspecified),
+            1 => Ok(ProtoStatus::Active),
+            2 => Ok(ProtoStatus::Disabled),
+            _ => Err(UnknownEnumValue(value)),
+        }
+    }
+}
+
+impl From<ProtoStatus> for i32 {
+    fn from(value: ProtoStatus) -> Self {
+        value as i32
+    }
+}
+
+#[derive(Mogrify)]
+#[mogrify(ProtoStatus, reject(Unspecified = "status must be specified"), reverse)]
+enum Status {
+    Active,
+    Disabled,
+}
+
+struct RawAccount {
+    status: i32,
+    previous: Option<i32>,
+    history: Vec<i32>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawAccount, reverse)]
+struct Account {
+    #[mogrify(enumeration = ProtoStatus)]
+    status: Status,
+    #[mogrify(enumeration = ProtoStatus)]
+    previous: Option<Status>,
+    #[mogrify(enumeration = ProtoStatus)]
+    history: Vec<Status>,
+}
+
+fn main() {
+    let raw = RawAccount {
+        status: 1,
+        previous: Some(2),
+        history: vec![1, 2],
+    };
+    let account: Account = raw.try_into().expect("successful conversion");
+    let raw: RawAccount = account.into();
+    assert_eq!(raw.history, vec![1, 2]);
+
+    let raw = RawAccount {
+        status: 0,
+        previous: None,
+        history: vec![1, 7],
+    };
+    let err = Account::try_from(raw).err().expect("failed conversion");
+    let messages = err
+        .failures()
+        .iter()
+        .map(|f| f.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        vec![
+            "status must be specified (at: .status::Unspecified)",
+            "Unknown enum value 7 (at: .history[1])",
+        ]
+    );
+}
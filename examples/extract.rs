@@ -0,0 +1,131 @@
+#![allow(dead_code)]
+use axum::body::Body;
+use axum::extract::{Form, Query};
+use axum::http::{Request, StatusCode};
+use axum::response::Response;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use http_body_util::BodyExt;
+use mogrify::{Mogrify, Parsed};
+use serde::Deserialize;
+use tower::ServiceExt;
+
+#[derive(Deserialize)]
+struct RawSignup {
+    email: Option<String>,
+    tags: Vec<i64>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawSignup)]
+struct Signup {
+    #[mogrify(require)]
+    email: String,
+    tags: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct RawSearch {
+    limit: i64,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawSearch)]
+struct Search {
+    limit: u8,
+}
+
+async fn signup(Parsed { value: signup, .. }: Parsed<Json<RawSignup>, Signup>) -> String {
+    signup.email
+}
+
+async fn search(search: Parsed<Query<RawSearch>, Search>) -> String {
+    search.into_inner().limit.to_string()
+}
+
+async fn login(Parsed { value: signup, .. }: Parsed<Form<RawSignup>, Signup>) -> String {
+    signup.email
+}
+
+fn app() -> Router {
+    Router::new()
+        .route("/signup", post(signup))
+        .route("/search", get(search))
+        .route("/login", post(login))
+}
+
+async fn send(request: Request<Body>) -> (StatusCode, String) {
+    let response: Response = app().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+fn json(body: &'static str) -> Request<Body> {
+    Request::post("/signup")
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+// the `errors` of a problem document, as (pointer, code) pairs
+fn errors(body: &str) -> Vec<(String, String)> {
+    let problem: mogrify::ProblemDetails = serde_json::from_str(body).expect("problem document");
+    problem
+        .errors
+        .into_iter()
+        .map(|e| (e.pointer, e.code))
+        .collect()
+}
+
+fn pair(pointer: &str, code: &str) -> (String, String) {
+    (pointer.to_string(), code.to_string())
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let (status, body) = send(json(r#"{"email": "a@b.c", "tags": [1]}"#)).await;
+    assert_eq!((status, body.as_str()), (StatusCode::OK, "a@b.c"));
+
+    // deserialization failures are reported at their path
+    let (status, body) = send(json(r#"{"email": "a@b.c", "tags": [1, "x"]}"#)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(errors(&body), vec![pair("/tags/1", "parse")]);
+
+    // and so are conversion failures
+    let (status, body) = send(json(r#"{"email": null, "tags": [1, -2]}"#)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        errors(&body),
+        vec![pair("/email", "required"), pair("/tags/1", "invalid")]
+    );
+
+    // malformed JSON is still axum's to reject
+    let (status, _) = send(json(r#"{"email": "#)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let search = |query: &str| {
+        Request::get(format!("/search?{query}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let (status, body) = send(search("limit=10")).await;
+    assert_eq!((status, body.as_str()), (StatusCode::OK, "10"));
+    let (status, body) = send(search("limit=ten")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(errors(&body), vec![pair("/limit", "parse")]);
+    let (status, body) = send(search("limit=500")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(errors(&body), vec![pair("/limit", "invalid")]);
+
+    let (status, body) = send(
+        Request::post("/login")
+            .header("content-type", "application/x-www-form-urlencoded")
+            .body(Body::from("email=a%40b.c"))
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    // missing fields are reported at the field
+    assert_eq!(errors(&body), vec![pair("/tags", "parse")]);
+}
@@ -0,0 +1,80 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CONVERTED: AtomicUsize = AtomicUsize::new(0);
+
+struct Reading(u16);
+impl TryFrom<i64> for Reading {
+    type Error = MogrificationError;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        CONVERTED.fetch_add(1, Ordering::Relaxed);
+        u16::try_from(value)
+            .map(Reading)
+            .map_err(|_| MogrificationError::new("reading out of range"))
+    }
+}
+
+struct RawRecord {
+    readings: Vec<i64>,
+    labelled: HashMap<String, Vec<i64>>,
+    sensor: Option<i64>,
+}
+
+// any failure discards the record, so there's no point converting the rest
+#[derive(Mogrify)]
+#[mogrify(RawRecord, fail_fast)]
+struct Record {
+    readings: Vec<Reading>,
+    labelled: HashMap<String, Vec<Reading>>,
+    #[mogrify(require)]
+    sensor: Reading,
+}
+
+enum RawEvent {
+    Single(i64),
+    Pair { first: i64, second: i64 },
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawEvent, fail_fast)]
+enum Event {
+    Single(Reading),
+    Pair { first: Reading, second: Reading },
+}
+
+fn main() {
+    let raw = RawRecord {
+        readings: vec![1, 2, 3],
+        labelled: HashMap::from([("a".to_string(), vec![4])]),
+        sensor: Some(5),
+    };
+    let _: Record = raw.try_into().expect("successful conversion");
+    assert_eq!(CONVERTED.swap(0, Ordering::Relaxed), 5);
+
+    let raw = RawRecord {
+        readings: (0..10_000).map(|i| if i == 1 { -1 } else { i }).collect(),
+        labelled: HashMap::from([("a".to_string(), vec![-1])]),
+        sensor: None,
+    };
+    let err = Record::try_from(raw).err().expect("failed conversion");
+    assert_eq!(err.failures().len(), 1);
+    assert_eq!(err.failures()[0].path(), ".readings[1]");
+    // stopped at the second reading, and never reached the other fields
+    assert_eq!(CONVERTED.load(Ordering::Relaxed), 2);
+
+    // failures inside enum variants are still pathed by the variant
+    let err = Event::try_from(RawEvent::Single(-1))
+        .err()
+        .expect("failed conversion");
+    assert_eq!(err.failures()[0].path(), "::Single");
+    let err = Event::try_from(RawEvent::Pair {
+        first: 1,
+        second: -1,
+    })
+    .err()
+    .expect("failed conversion");
+    assert_eq!(err.failures().len(), 1);
+    assert_eq!(err.failures()[0].path(), "::Pair.second");
+}
@@ -0,0 +1,127 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+struct RawItem {
+    name: String,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawItem, reverse)]
+struct Item {
+    name: String,
+}
+
+struct RawPage<T> {
+    items: Vec<T>,
+    first: Option<T>,
+    lookup: HashMap<String, T>,
+    total: u32,
+}
+
+// `R` only exists on the source, so it's introduced with `for<R>`
+#[derive(Mogrify)]
+#[mogrify(for<R> RawPage<R>, reverse)]
+struct Page<T> {
+    items: Vec<T>,
+    first: Option<T>,
+    lookup: HashMap<String, T>,
+    total: u32,
+}
+
+struct RawEnvelope<'a, T> {
+    label: Cow<'a, str>,
+    data: Option<T>,
+}
+
+// `T` is shared with the source, so it converts into itself and needs no bounds
+#[derive(Mogrify)]
+#[mogrify(RawEnvelope<'a, T>)]
+struct Envelope<'a, T: Clone> {
+    label: Cow<'a, str>,
+    #[mogrify(require)]
+    data: T,
+}
+
+enum RawResult<T> {
+    Found(T),
+    Missing(()),
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawResult<RawItem>, grpc)]
+enum Lookup<T> {
+    Found(T),
+    Missing,
+}
+
+struct StringWrap(String);
+
+#[derive(Mogrify)]
+#[mogrify(RawPage<String>, bound = "T: TryFrom<String, Error = MogrificationError>")]
+struct Wrapped<T> {
+    items: Vec<T>,
+    first: Option<T>,
+    lookup: HashMap<String, T>,
+    total: u32,
+}
+
+// any type can fill in the source's params, not just paths
+#[derive(Mogrify)]
+#[mogrify(RawPage<&'static str>)]
+struct Labels {
+    items: Vec<String>,
+    first: Option<String>,
+    lookup: HashMap<String, String>,
+    total: u32,
+}
+
+impl TryFrom<String> for StringWrap {
+    type Error = MogrificationError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(StringWrap(value))
+    }
+}
+
+fn main() {
+    let raw = RawPage {
+        items: vec![RawItem {
+            name: "one".to_string(),
+        }],
+        first: None,
+        lookup: HashMap::new(),
+        total: 1,
+    };
+    let page: Page<Item> = raw.try_into().expect("successful conversion");
+    let raw: RawPage<RawItem> = page.into();
+    assert_eq!(raw.items[0].name, "one");
+
+    let raw = RawEnvelope {
+        label: Cow::Borrowed("label"),
+        data: Some(3u32),
+    };
+    let _: Envelope<u32> = raw.try_into().expect("successful conversion");
+
+    let raw = RawResult::Found(RawItem {
+        name: "one".to_string(),
+    });
+    let _: Lookup<Item> = raw.try_into().expect("successful conversion");
+
+    let raw = RawPage {
+        items: vec!["one".to_string()],
+        first: None,
+        lookup: HashMap::new(),
+        total: 1,
+    };
+    let _: Wrapped<StringWrap> = raw.try_into().expect("successful conversion");
+
+    let raw = RawPage {
+        items: vec!["one"],
+        first: Some("one"),
+        lookup: HashMap::new(),
+        total: 1,
+    };
+    let labels: Labels = raw.try_into().expect("successful conversion");
+    assert_eq!(labels.first.as_deref(), Some("one"));
+}
@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+use mogrify::Mogrify;
+
+#[derive(Default)]
+struct RawInput {
+    name: String,
+    count: u32,
+    internal_id: u64,
+}
+
+enum RawKind {
+    First { label: String, legacy: bool },
+    Second(u32, u32),
+}
+
+// without `ignore_extra`, rustc reports `internal_id` as a missing field against `RawInput` above
+#[derive(Mogrify)]
+#[mogrify(RawInput, ignore_extra, reverse)]
+struct MogrifiedInput {
+    name: String,
+    count: u32,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawKind, ignore_extra)]
+enum MogrifiedKind {
+    First { label: String },
+    Second(u32),
+}
+
+fn main() {
+    let raw = RawInput {
+        name: "name".to_string(),
+        count: 3,
+        internal_id: 7,
+    };
+
+    let mogrified: MogrifiedInput = raw.try_into().expect("successful conversion");
+    let raw: RawInput = mogrified.into();
+    assert_eq!(raw.internal_id, 0);
+
+    let raw = RawKind::Second(1, 2);
+    let _: MogrifiedKind = raw.try_into().expect("successful conversion");
+}
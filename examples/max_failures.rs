@@ -0,0 +1,91 @@
+#![allow(dead_code)]
+use mogrify::util::{mogrify_seq_in, Scope};
+use mogrify::{MogrificationError, Mogrify};
+
+struct Reading(u16);
+impl TryFrom<i64> for Reading {
+    type Error = MogrificationError;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u16::try_from(value)
+            .map(Reading)
+            .map_err(|_| MogrificationError::new("reading out of range"))
+    }
+}
+
+struct RawUpload {
+    readings: Vec<i64>,
+    backup: Vec<i64>,
+    sensor: Option<i64>,
+}
+
+// keep hostile uploads from allocating a failure per element
+#[derive(Mogrify)]
+#[mogrify(RawUpload, max_failures = 3)]
+struct Upload {
+    readings: Vec<Reading>,
+    backup: Vec<Reading>,
+    #[mogrify(require)]
+    sensor: Reading,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawUpload)]
+struct Uncapped {
+    readings: Vec<Reading>,
+    backup: Vec<Reading>,
+    #[mogrify(require)]
+    sensor: Reading,
+}
+
+fn main() {
+    let raw = RawUpload {
+        readings: vec![-1; 100_000],
+        backup: vec![-1, 2],
+        sensor: None,
+    };
+    let err = Upload::try_from(raw).err().expect("failed conversion");
+    assert_eq!(err.failures().len(), 3);
+    assert_eq!(err.dropped(), 100_000 - 3 + 1 + 1);
+    assert_eq!(err.failures()[2].path(), ".readings[2]");
+
+    let rendered = err.to_string();
+    assert!(rendered.starts_with("found 100002 mogrify failures\n"));
+    assert!(rendered.ends_with("    ... and 99999 more\n"));
+
+    // merging errors by hand can be capped too
+    let mut merged = MogrificationError::capped(3);
+    merged.collect(MogrificationError::new("first"));
+    merged.collect(err);
+    assert_eq!(merged.failures().len(), 3);
+    assert_eq!(merged.dropped(), 100_000);
+
+    // and so can hand-written ones
+    let condensed = MogrificationError::condense_capped(
+        vec![
+            MogrificationError::new("a"),
+            MogrificationError::new("b"),
+            MogrificationError::new("c"),
+        ],
+        2,
+    )
+    .expect_err("failed condense");
+    assert_eq!(condensed.dropped(), 1);
+    let scope = Scope {
+        max_failures: 2,
+        ..Scope::default()
+    };
+    let err = mogrify_seq_in::<_, Reading, _, Vec<_>, _, _>(scope, Reading::try_from, vec![-1; 5])
+        .err()
+        .expect("failed conversion");
+    assert_eq!((err.failures().len(), err.dropped()), (2, 3));
+
+    // other conversions aren't affected
+    let raw = RawUpload {
+        readings: vec![-1; 10],
+        backup: vec![],
+        sensor: Some(1),
+    };
+    let err = Uncapped::try_from(raw).err().expect("failed conversion");
+    assert_eq!(err.failures().len(), 10);
+    assert_eq!(err.dropped(), 0);
+}
@@ -0,0 +1,121 @@
+#![allow(dead_code)]
+use mogrify::{English, MessageCatalog, MessageParams, MogrificationError, Mogrify};
+
+struct German;
+
+impl MessageCatalog for German {
+    fn render(&self, key: &str, params: &MessageParams) -> Option<String> {
+        let param = |name| params.get(name).unwrap_or_default();
+        match key {
+            "required" => Some("Wert ist erforderlich".to_string()),
+            "length.between" => Some(format!(
+                "muss zwischen {} und {} Zeichen lang sein",
+                param("min"),
+                param("max")
+            )),
+            "range.min" => Some(format!("muss mindestens {} sein", param("min"))),
+            "user.reserved" => Some(format!("{} ist reserviert", param("name"))),
+            // everything else falls back to English
+            _ => None,
+        }
+    }
+}
+
+struct RawUser {
+    name: String,
+    age: i64,
+    email: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawUser)]
+struct User {
+    #[mogrify(len(min = 3, max = 16), and_then = unreserved)]
+    name: String,
+    #[mogrify(range(min = 13))]
+    age: i64,
+    #[mogrify(require)]
+    email: String,
+    #[mogrify(whole(non_empty))]
+    tags: Vec<String>,
+}
+
+// custom failures can be keyed too, keeping their own message as the English one
+fn unreserved(name: String) -> Result<String, MogrificationError> {
+    if name == "admin" {
+        Err(MogrificationError::new(format!("{name} is reserved"))
+            .keyed("user.reserved", MessageParams::new().with("name", &name)))
+    } else {
+        Ok(name)
+    }
+}
+
+fn main() {
+    let err = User::try_from(RawUser {
+        name: "al".to_string(),
+        age: 9,
+        email: None,
+        tags: vec![],
+    })
+    .err()
+    .expect("failed conversion");
+
+    let failures = err.failures();
+    assert_eq!(failures[0].key(), Some("length.between"));
+    assert_eq!(failures[0].params().get("actual"), Some("2"));
+    assert_eq!(failures[1].params().get("actual"), Some("9"));
+
+    let german = failures
+        .iter()
+        .map(|f| f.message_in(&German))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        german,
+        vec![
+            "muss zwischen 3 und 16 Zeichen lang sein",
+            "muss mindestens 13 sein",
+            "Wert ist erforderlich",
+            "must not be empty",
+        ]
+    );
+
+    // `Display` and `message` are the English catalog's
+    let english = failures
+        .iter()
+        .map(|f| f.message_in(&English))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        english,
+        failures.iter().map(|f| f.message()).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        failures[0].to_string(),
+        "must be between 3 and 16 characters (at: .name)"
+    );
+
+    let err = User::try_from(RawUser {
+        name: "admin".to_string(),
+        age: 30,
+        email: Some("admin@example.com".to_string()),
+        tags: vec!["ops".to_string()],
+    })
+    .err()
+    .expect("failed conversion");
+    assert_eq!(err.to_string(), "admin is reserved (at: .name)");
+    assert_eq!(
+        err.failures()[0].message_in(&German),
+        "admin ist reserviert"
+    );
+
+    // keying an error only keys the failures that aren't already
+    let mut merged = MogrificationError::new("first");
+    merged.collect(err);
+    let merged = merged.keyed("user.invalid", MessageParams::new());
+    let keys = merged
+        .failures()
+        .iter()
+        .map(|f| f.key())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![Some("user.invalid"), Some("user.reserved")]);
+}
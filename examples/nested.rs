@@ -0,0 +1,94 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+use std::collections::HashMap;
+
+struct Positive(i64);
+impl TryFrom<i64> for Positive {
+    type Error = MogrificationError;
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        if value > 0 {
+            Ok(Positive(value))
+        } else {
+            Err(MogrificationError::new("must be positive"))
+        }
+    }
+}
+impl From<Positive> for i64 {
+    fn from(value: Positive) -> Self {
+        value.0
+    }
+}
+
+struct RawInput {
+    tags: HashMap<String, Vec<i64>>,
+    maybe: Option<Vec<i64>>,
+    sparse: Vec<Option<i64>>,
+    grid: Vec<Vec<i64>>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawInput, reverse)]
+struct Input {
+    tags: HashMap<String, Vec<Positive>>,
+    maybe: Option<Vec<Positive>>,
+    sparse: Vec<Option<Positive>>,
+    grid: Vec<Vec<Positive>>,
+}
+
+struct RawCodes {
+    codes: Vec<Option<String>>,
+}
+
+// `parse` and `and_then` apply to the innermost element
+#[derive(Mogrify)]
+#[mogrify(RawCodes)]
+struct Codes {
+    #[mogrify(parse = mogrify::util::force_parse, and_then = check_code)]
+    codes: Vec<Option<u32>>,
+}
+
+fn check_code(code: u32) -> Result<u32, MogrificationError> {
+    if code < 1000 {
+        Ok(code)
+    } else {
+        Err(MogrificationError::new("code too large"))
+    }
+}
+
+fn main() {
+    let raw = RawInput {
+        tags: HashMap::from([("en".to_string(), vec![1, 2, 3, 4])]),
+        maybe: Some(vec![1]),
+        sparse: vec![Some(1), None],
+        grid: vec![vec![1], vec![2, 3]],
+    };
+    let input: Input = raw.try_into().expect("successful conversion");
+    let raw: RawInput = input.into();
+    assert_eq!(raw.grid[1][1], 3);
+    assert_eq!(raw.sparse[1], None);
+
+    let raw = RawInput {
+        tags: HashMap::from([("en".to_string(), vec![1, 2, 3, -4])]),
+        maybe: Some(vec![1, 0]),
+        sparse: vec![None, Some(-1)],
+        grid: vec![vec![1], vec![2, -3]],
+    };
+    let err = Input::try_from(raw).err().expect("failed conversion");
+    let paths = err.failures().iter().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![".tags[\"en\"][3]", ".maybe[1]", ".sparse[1]", ".grid[1][1]"]
+    );
+
+    let raw = RawCodes {
+        codes: vec![
+            Some("1".to_string()),
+            None,
+            Some("x".to_string()),
+            Some("5000".to_string()),
+        ],
+    };
+    let err = Codes::try_from(raw).err().expect("failed conversion");
+    let paths = err.failures().iter().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![".codes[2]", ".codes[3]"]);
+}
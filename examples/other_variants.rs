@@ -0,0 +1,79 @@
+#![allow(dead_code)]
+use mogrify::Mogrify;
+
+enum RawColor {
+    Unspecified(()),
+    Red(()),
+    Green(()),
+    Blue(()),
+    Custom { hex: String },
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawColor, grpc, reject(Unspecified = "color must be specified"))]
+enum Color {
+    Red,
+    Green,
+    #[mogrify(other)]
+    Other,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawColor, grpc, reverse)]
+enum Primary {
+    Red,
+    Green,
+    Blue,
+    #[mogrify(other, source = Unspecified)]
+    Unknown,
+}
+
+// the same rejection, declared on the variant it maps from
+#[derive(Mogrify)]
+#[mogrify(RawColor, grpc, reverse)]
+enum Shade {
+    #[mogrify(reject = "shade must be specified")]
+    Unspecified,
+    Red,
+    Green,
+    Blue,
+    #[mogrify(source = Custom)]
+    Custom {
+        hex: String,
+    },
+}
+
+fn main() {
+    let color: Color = RawColor::Blue(())
+        .try_into()
+        .expect("successful conversion");
+    assert!(matches!(color, Color::Other));
+
+    let err = Color::try_from(RawColor::Unspecified(()))
+        .err()
+        .expect("failed conversion");
+    assert_eq!(
+        err.to_string(),
+        "color must be specified (at: ::Unspecified)"
+    );
+
+    let primary: Primary = RawColor::Custom {
+        hex: "#fff".to_string(),
+    }
+    .try_into()
+    .expect("successful conversion");
+    let raw: RawColor = primary.into();
+    assert!(matches!(raw, RawColor::Unspecified(())));
+
+    let err = Shade::try_from(RawColor::Unspecified(()))
+        .err()
+        .expect("failed conversion");
+    assert_eq!(
+        err.to_string(),
+        "shade must be specified (at: ::Unspecified)"
+    );
+    let shade: Shade = RawColor::Blue(())
+        .try_into()
+        .expect("successful conversion");
+    assert!(matches!(shade, Shade::Blue));
+}
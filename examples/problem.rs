@@ -0,0 +1,92 @@
+#![allow(dead_code)]
+use actix_web::test as actix_test;
+use axum::body::Body;
+use axum::http::{Request, StatusCode};
+use axum::routing::post;
+use axum::{Json, Router};
+use http_body_util::BodyExt;
+use mogrify::{MogrificationError, Mogrify, ProblemDetails};
+use serde::Deserialize;
+use tower::ServiceExt;
+
+#[derive(Deserialize)]
+struct RawSignup {
+    email: Option<String>,
+    tags: Vec<i64>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawSignup)]
+struct Signup {
+    #[mogrify(require)]
+    email: String,
+    tags: Vec<u8>,
+}
+
+async fn axum_signup(Json(raw): Json<RawSignup>) -> Result<StatusCode, MogrificationError> {
+    let _: Signup = raw.try_into()?;
+    Ok(StatusCode::CREATED)
+}
+
+async fn actix_signup(
+    raw: actix_web::web::Json<RawSignup>,
+) -> Result<actix_web::HttpResponse, MogrificationError> {
+    let _: Signup = raw.into_inner().try_into()?;
+    Ok(actix_web::HttpResponse::Created().finish())
+}
+
+const BODY: &str = r#"{"email": null, "tags": [1, -2]}"#;
+
+fn expected() -> serde_json::Value {
+    serde_json::json!({
+        "type": "about:blank",
+        "title": "Unprocessable Entity",
+        "status": 422,
+        "detail": "found 2 mogrify failures",
+        "errors": [
+            {"pointer": "/email", "detail": "Value is required", "code": "required"},
+            {"pointer": "/tags/1", "detail": "mogrify failure", "code": "invalid"},
+        ]
+    })
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let app = Router::new().route("/signup", post(axum_signup));
+    let response = app
+        .oneshot(
+            Request::post("/signup")
+                .header("content-type", "application/json")
+                .body(Body::from(BODY))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response.headers()["content-type"],
+        ProblemDetails::CONTENT_TYPE
+    );
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body, expected());
+
+    // actix-web responds with the same document
+    let app = actix_test::init_service(
+        actix_web::App::new().route("/signup", actix_web::web::post().to(actix_signup)),
+    )
+    .await;
+    let request = actix_test::TestRequest::post()
+        .uri("/signup")
+        .insert_header(("content-type", "application/json"))
+        .set_payload(BODY)
+        .to_request();
+    let response = actix_test::call_service(&app, request).await;
+    assert_eq!(response.status().as_u16(), 422);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        ProblemDetails::CONTENT_TYPE
+    );
+    let body: ProblemDetails = actix_test::read_body_json(response).await;
+    assert_eq!(serde_json::to_value(body).unwrap(), expected());
+}
@@ -0,0 +1,52 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+
+struct RawInput {
+    user_id: String,
+    name: String,
+}
+
+struct StringWrap(String);
+impl TryFrom<String> for StringWrap {
+    type Error = MogrificationError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if &value == "error" {
+            Err(MogrificationError::new("invalid string"))
+        } else {
+            Ok(StringWrap(value))
+        }
+    }
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawInput)]
+struct MogrifiedInput {
+    #[mogrify(from = "user_id")]
+    owner: StringWrap,
+    name: StringWrap,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawInput)]
+struct MogrifiedPair(
+    #[mogrify(from = "user_id")] StringWrap,
+    #[mogrify(from = "name")] StringWrap,
+);
+
+fn main() {
+    let raw = RawInput {
+        user_id: "error".to_string(),
+        name: "name".to_string(),
+    };
+
+    let err = MogrifiedInput::try_from(raw)
+        .err()
+        .expect("failed conversion");
+    assert_eq!(err.failures()[0].path(), ".user_id");
+
+    let raw = RawInput {
+        user_id: "user".to_string(),
+        name: "name".to_string(),
+    };
+    let _: MogrifiedPair = raw.try_into().expect("successful conversion");
+}
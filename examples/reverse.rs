@@ -0,0 +1,69 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+use std::collections::HashMap;
+
+struct RawInput {
+    name: String,
+    maybe_foo: Option<String>,
+    count: Option<u32>,
+    items: Vec<String>,
+    map: HashMap<String, String>,
+    kind: RawKind,
+}
+
+enum RawKind {
+    First(()),
+    Second { label: String },
+    Third(u32),
+}
+
+#[derive(Eq, PartialEq, Hash)]
+struct StringWrap(String);
+impl TryFrom<String> for StringWrap {
+    type Error = MogrificationError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Ok(StringWrap(value))
+    }
+}
+impl From<StringWrap> for String {
+    fn from(value: StringWrap) -> Self {
+        value.0
+    }
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawKind, grpc, reverse)]
+enum MogrifiedKind {
+    First,
+    Second { label: StringWrap },
+    Third(u32),
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawInput, reverse)]
+struct MogrifiedInput {
+    name: StringWrap,
+    #[mogrify(require)]
+    maybe_foo: StringWrap,
+    #[mogrify(default = 3)]
+    count: u32,
+    items: Vec<StringWrap>,
+    map: HashMap<StringWrap, StringWrap>,
+    kind: MogrifiedKind,
+}
+
+fn main() {
+    let raw = RawInput {
+        name: "name".to_string(),
+        maybe_foo: Some("foo".to_string()),
+        count: None,
+        items: vec!["item".to_string()],
+        map: HashMap::new(),
+        kind: RawKind::First(()),
+    };
+
+    let mogrified: MogrifiedInput = raw.try_into().expect("successful conversion");
+    let raw: RawInput = mogrified.into();
+    assert_eq!(raw.count, Some(3));
+    assert!(matches!(raw.kind, RawKind::First(())));
+}
@@ -0,0 +1,59 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, MogrificationReport, Mogrify, PathPart};
+use std::collections::HashMap;
+
+struct RawProfile {
+    age: String,
+    tags: HashMap<String, Vec<i64>>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawProfile)]
+struct Profile {
+    #[mogrify(parse = mogrify::util::mogrify_parse)]
+    age: u8,
+    tags: HashMap<String, Vec<u8>>,
+}
+
+fn main() {
+    let raw = RawProfile {
+        age: "old".to_string(),
+        tags: HashMap::from([("en".to_string(), vec![1, 2, 3, -4])]),
+    };
+    let err: MogrificationError = Profile::try_from(raw).err().expect("failed conversion");
+
+    let body = serde_json::to_value(&err).expect("serializable");
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "failures": [
+                {
+                    "path": ".age",
+                    "segments": [{ "field": "age" }],
+                    "message": "invalid digit found in string",
+                    "code": "parse",
+                    "key": "parse",
+                    "params": { "error": "invalid digit found in string" },
+                    "underlying": ["invalid digit found in string"],
+                },
+                {
+                    "path": ".tags[\"en\"][3]",
+                    "segments": [{ "field": "tags" }, { "key": "en" }, { "index": 3 }],
+                    "message": "mogrify failure",
+                    "code": "invalid",
+                    "key": "invalid",
+                    "params": { "error": "out of range integral type conversion attempted" },
+                    "underlying": ["out of range integral type conversion attempted"],
+                },
+            ]
+        })
+    );
+
+    // clients can read the body back into a plain report
+    let report: MogrificationReport = serde_json::from_value(body).expect("deserializable");
+    assert_eq!(report, MogrificationReport::from(&err));
+    assert_eq!(
+        report.failures[1].segments[1],
+        PathPart::Key("en".to_string())
+    );
+}
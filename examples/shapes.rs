@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify};
+
+struct Uuid(String);
+impl TryFrom<String> for Uuid {
+    type Error = MogrificationError;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        if value.len() == 36 {
+            Ok(Uuid(value))
+        } else {
+            Err(MogrificationError::new("invalid uuid"))
+        }
+    }
+}
+impl From<Uuid> for String {
+    fn from(value: Uuid) -> Self {
+        value.0
+    }
+}
+
+struct RawId(String);
+
+#[derive(Mogrify)]
+#[mogrify(RawId, reverse)]
+struct Id(Uuid);
+
+struct RawRange(u32, u32);
+
+#[derive(Mogrify)]
+#[mogrify(RawRange, shape = tuple, reverse)]
+struct Range {
+    start: u32,
+    end: u32,
+}
+
+struct RawAck;
+
+#[derive(Mogrify)]
+#[mogrify(RawAck, reverse)]
+struct Ack;
+
+fn main() {
+    let err = Id::try_from(RawId("nope".to_string()))
+        .err()
+        .expect("failed conversion");
+    assert_eq!(err.failures()[0].path(), "");
+
+    let range: Range = RawRange(1, 2).try_into().expect("successful conversion");
+    assert_eq!(range.end, 2);
+    let raw: RawRange = range.into();
+    assert_eq!(raw.0, 1);
+
+    let _: Ack = RawAck.try_into().expect("successful conversion");
+}
@@ -0,0 +1,94 @@
+#![allow(dead_code)]
+use mogrify::Mogrify;
+use tonic::{Code, Status};
+use tonic_types::StatusExt;
+
+struct RawSignup {
+    email: Option<String>,
+    age: String,
+    tags: Vec<i64>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawSignup)]
+struct Signup {
+    #[mogrify(require)]
+    email: String,
+    #[mogrify(parse = mogrify::util::mogrify_parse)]
+    age: u8,
+    tags: Vec<u8>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawSignup, max_failures = 1)]
+struct CappedSignup {
+    #[mogrify(require)]
+    email: String,
+    #[mogrify(parse = mogrify::util::mogrify_parse)]
+    age: u8,
+    tags: Vec<u8>,
+}
+
+// what a handler returning `Result<_, Status>` does with `?`
+fn handle(raw: RawSignup) -> Result<Signup, Status> {
+    Ok(raw.try_into()?)
+}
+
+fn main() {
+    let status = handle(RawSignup {
+        email: None,
+        age: "old".to_string(),
+        tags: vec![1, -2],
+    })
+    .err()
+    .expect("failed conversion");
+    assert_eq!(status.code(), Code::InvalidArgument);
+    assert!(status.message().starts_with("found 3 mogrify failures"));
+
+    // clients decode the same structure back out of the status details
+    let bad_request = status
+        .get_details_bad_request()
+        .expect("bad request details");
+    let violations = bad_request
+        .field_violations
+        .iter()
+        .map(|v| (v.field.as_str(), v.description.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        violations,
+        vec![
+            ("email", "Value is required"),
+            ("age", "invalid digit found in string"),
+            ("tags[1]", "mogrify failure"),
+        ]
+    );
+    // along with the message keys and params, for rendering them in other languages
+    let error_info = status.get_details_error_info().expect("error info details");
+    let keys = (0..3)
+        .map(|i| error_info.metadata[&format!("field_violations[{i}].key")].as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec!["required", "parse", "invalid"]);
+    assert_eq!(
+        error_info.metadata["field_violations[1].params.error"],
+        "invalid digit found in string"
+    );
+    // nothing was dropped
+    assert!(!error_info.metadata.contains_key("dropped"));
+
+    let status = Status::from(
+        CappedSignup::try_from(RawSignup {
+            email: None,
+            age: "old".to_string(),
+            tags: vec![1, -2],
+        })
+        .err()
+        .expect("failed conversion"),
+    );
+    let bad_request = status
+        .get_details_bad_request()
+        .expect("bad request details");
+    assert_eq!(bad_request.field_violations.len(), 1);
+    let error_info = status.get_details_error_info().expect("error info details");
+    assert_eq!(error_info.domain, "mogrify");
+    assert_eq!(error_info.metadata["dropped"], "2");
+}
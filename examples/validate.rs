@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+use mogrify::{MogrificationError, Mogrify, Pathed};
+
+struct RawBooking {
+    start: u32,
+    end: u32,
+    email: Option<String>,
+    phone: Option<String>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawBooking, validate = check_booking)]
+struct Booking {
+    start: u32,
+    end: u32,
+    email: Option<String>,
+    phone: Option<String>,
+}
+
+fn check_booking(booking: &Booking) -> Result<(), MogrificationError> {
+    let mut errors = Vec::new();
+    if booking.end <= booking.start {
+        errors.push(MogrificationError::new("end must be after start").at_field("end"));
+    }
+    if booking.email.is_none() && booking.phone.is_none() {
+        errors.push(MogrificationError::new("either email or phone is required").at_field("email"));
+        errors.push(MogrificationError::new("either email or phone is required").at_field("phone"));
+    }
+    MogrificationError::condense(errors)
+}
+
+fn main() {
+    let raw = RawBooking {
+        start: 5,
+        end: 3,
+        email: None,
+        phone: None,
+    };
+
+    let err = Booking::try_from(raw).err().expect("failed validation");
+    let paths = err.failures().iter().map(|f| f.path()).collect::<Vec<_>>();
+    assert_eq!(paths, vec![".end", ".email", ".phone"]);
+}
@@ -0,0 +1,92 @@
+#![allow(dead_code)]
+use mogrify::Mogrify;
+
+struct RawProfile {
+    username: String,
+    age: i64,
+    score: f64,
+    tags: Vec<String>,
+    nickname: Option<String>,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawProfile)]
+struct Profile {
+    #[mogrify(len(min = 3, max = 16), pattern = "^[a-z_]+$")]
+    username: String,
+    #[mogrify(range(min = 13, max = 130))]
+    age: u8,
+    #[mogrify(range(min = 0.0, max = 1.0))]
+    score: f64,
+    // element checks apply to each tag, `whole(...)` checks the list itself
+    #[mogrify(non_empty, len(max = 8), whole(len(max = 3)))]
+    tags: Vec<String>,
+    #[mogrify(len(min = 2))]
+    nickname: Option<String>,
+}
+
+fn main() {
+    let raw = RawProfile {
+        username: "ferris".to_string(),
+        age: 30,
+        score: 0.5,
+        tags: vec!["rust".to_string()],
+        nickname: None,
+    };
+    let _: Profile = raw.try_into().expect("successful conversion");
+
+    let raw = RawProfile {
+        username: "Ferris!".to_string(),
+        age: 9,
+        score: 1.5,
+        tags: vec![
+            "rust".to_string(),
+            "".to_string(),
+            "crustaceans".to_string(),
+            "crab".to_string(),
+        ],
+        nickname: Some("f".to_string()),
+    };
+    let err = Profile::try_from(raw).err().expect("failed conversion");
+    let failures = err
+        .failures()
+        .iter()
+        .map(|f| (f.path(), f.message().to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        failures,
+        vec![
+            (
+                ".username".to_string(),
+                "must match the pattern ^[a-z_]+$".to_string()
+            ),
+            (".age".to_string(), "must be between 13 and 130".to_string()),
+            (".score".to_string(), "must be between 0 and 1".to_string()),
+            (".tags[1]".to_string(), "must not be empty".to_string()),
+            (
+                ".tags[2]".to_string(),
+                "must be at most 8 characters".to_string()
+            ),
+            (
+                ".nickname".to_string(),
+                "must be at least 2 characters".to_string()
+            ),
+        ]
+    );
+
+    let raw = RawProfile {
+        username: "ferris".to_string(),
+        age: 30,
+        score: 0.5,
+        tags: vec![
+            "a".to_string(),
+            "b".to_string(),
+            "c".to_string(),
+            "d".to_string(),
+        ],
+        nickname: None,
+    };
+    let err = Profile::try_from(raw).err().expect("failed conversion");
+    assert_eq!(err.failures()[0].path(), ".tags");
+    assert_eq!(err.failures()[0].message(), "must be at most 3 items");
+}
@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+use mogrify::{FailureCode, MogrificationError, Mogrify, TryFromWarned, Warnings};
+
+enum RawColor {
+    Unspecified(()),
+    Red(()),
+    Green(()),
+    Blue(()),
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawColor, grpc, warnings)]
+enum Color {
+    Red,
+    Green,
+    #[mogrify(other, warn = "unknown color")]
+    Other,
+}
+
+struct RawLight {
+    colors: Vec<RawColor>,
+    brightness: i64,
+}
+
+#[derive(Mogrify)]
+#[mogrify(RawLight, warnings)]
+struct Light {
+    // nested conversions share the warnings
+    #[mogrify(context)]
+    colors: Vec<Color>,
+    #[mogrify(and_then = clamp)]
+    brightness: i64,
+}
+
+// anything brighter is accepted, but probably a client bug
+fn clamp(brightness: i64, warnings: &Warnings) -> Result<i64, MogrificationError> {
+    if brightness < 0 {
+        return Err(MogrificationError::new("brightness can't be negative"));
+    }
+    if brightness > 100 {
+        warnings.warn(MogrificationError::new_coded(
+            FailureCode::Custom("clamped".to_string()),
+            "brightness clamped to 100",
+        ));
+    }
+    Ok(brightness.min(100))
+}
+
+fn main() {
+    let raw = || RawLight {
+        colors: vec![RawColor::Red(()), RawColor::Blue(())],
+        brightness: 150,
+    };
+
+    // plain conversions drop the warnings
+    let light: Light = raw().try_into().expect("successful conversion");
+    assert_eq!(light.brightness, 100);
+
+    let warned = Light::try_from_warned(raw()).expect("successful conversion");
+    assert_eq!(warned.value.brightness, 100);
+    let warnings = warned
+        .warnings
+        .iter()
+        .map(|w| (w.path(), w.code().to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        warnings,
+        vec![
+            (".colors[1]".to_string(), "unknown_variant".to_string()),
+            (".brightness".to_string(), "clamped".to_string()),
+        ]
+    );
+
+    // strict mode promotes them to failures
+    let err = Light::try_from_strict(raw())
+        .err()
+        .expect("failed conversion");
+    assert_eq!(err.failures().len(), 2);
+    assert_eq!(
+        err.failures()[0].to_string(),
+        "unknown color (at: .colors[1])"
+    );
+
+    let light = Light::try_from_strict(RawLight {
+        colors: vec![RawColor::Green(())],
+        brightness: 50,
+    })
+    .expect("successful conversion");
+    assert!(matches!(light.colors[..], [Color::Green]));
+
+    // warnings are returned alongside errors too
+    let err = Light::try_from_warned(RawLight {
+        colors: vec![RawColor::Unspecified(())],
+        brightness: -1,
+    })
+    .err()
+    .expect("failed conversion");
+    assert_eq!(err.value.failures()[0].path(), ".brightness");
+    assert_eq!(err.warnings[0].path(), ".colors[0]");
+}
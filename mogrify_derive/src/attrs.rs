@@ -1,8 +1,14 @@
 use proc_macro2::Ident;
+use syn::meta::ParseNestedMeta;
 use syn::parse::ParseStream;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{parse_quote, Attribute, Error, Expr, Path, Token, TypePath};
+use syn::{
+    parenthesized, parse_quote, Attribute, Error, Expr, Generics, LitStr, Path, Token, Type,
+    TypePath, WherePredicate,
+};
 
+#[derive(Clone)]
 pub(crate) struct MogrifyFieldAttrs {
     // Option::ok()
     pub(crate) require: bool,
@@ -14,16 +20,126 @@ pub(crate) struct MogrifyFieldAttrs {
     pub(crate) default: Option<Option<Expr>>,
     // Final check
     pub(crate) and_then: Option<TypePath>,
+    // Name of the source field, when it differs from the target field
+    pub(crate) from: Option<Ident>,
+    // Convert a prost `i32` through the given enumeration before TryFrom
+    pub(crate) enumeration: Option<TypePath>,
+    // Convert with `TryFromWith`, passing along the parent's context
+    pub(crate) context: bool,
+    // Built-in checks on the innermost element, after conversion and before `and_then`
+    pub(crate) validators: Vec<MogrifyValidator>,
+    // Built-in checks on the whole converted field, written `whole(...)`
+    pub(crate) whole: Vec<MogrifyValidator>,
+}
+
+#[derive(Clone)]
+pub(crate) enum MogrifyValidator {
+    // `len(min = 1, max = 64)`, in characters for strings or items for collections
+    Len {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    // `range(min = 0, max = 100)`, inclusive
+    Range {
+        min: Option<Expr>,
+        max: Option<Expr>,
+    },
+    // `pattern = "^[a-z]+$"`
+    Pattern(LitStr),
+    // `non_empty`
+    NonEmpty,
+}
+
+// Parses a single validator, or `None` if the meta isn't one
+fn parse_validator(meta: &ParseNestedMeta) -> Result<Option<MogrifyValidator>, Error> {
+    if meta.path.is_ident("non_empty") {
+        return Ok(Some(MogrifyValidator::NonEmpty));
+    }
+    if meta.path.is_ident("pattern") {
+        let pattern: LitStr = meta.value()?.parse()?;
+        // checked here, so a bad pattern fails to compile rather than panicking on first use
+        if let Err(err) = regex_syntax::Parser::new().parse(&pattern.value()) {
+            return Err(Error::new_spanned(
+                pattern,
+                format!("invalid pattern: {err}"),
+            ));
+        }
+        return Ok(Some(MogrifyValidator::Pattern(pattern)));
+    }
+    if meta.path.is_ident("len") || meta.path.is_ident("range") {
+        let mut min = None;
+        let mut max = None;
+        meta.parse_nested_meta(|bound| {
+            if bound.path.is_ident("min") {
+                min = Some(bound.value()?.parse::<Expr>()?);
+                return Ok(());
+            }
+            if bound.path.is_ident("max") {
+                max = Some(bound.value()?.parse::<Expr>()?);
+                return Ok(());
+            }
+            Err(bound.error(r#"expected either "min = ..." or "max = ...""#))
+        })?;
+        if min.is_none() && max.is_none() {
+            return Err(meta.error(r#"expected at least one of "min = ..." or "max = ...""#));
+        }
+        return Ok(Some(if meta.path.is_ident("len") {
+            MogrifyValidator::Len { min, max }
+        } else {
+            MogrifyValidator::Range { min, max }
+        }));
+    }
+    Ok(None)
 }
 
 pub(crate) struct MogrifyStructAttrs {
     pub(crate) source: TypePath,
+    // Generic params only present on the source, declared as `for<R> RawFoo<R>`
+    pub(crate) generics: Generics,
     // For enums, map any unit variants `Unit` to an empty tuple `Unit(())`
     pub(crate) grpc: bool,
+    // Also generate the infallible `From<Target> for Source`
+    pub(crate) reverse: bool,
+    // Skip source fields without a matching target field, rather than failing to compile on them
+    pub(crate) ignore_extra: bool,
+    // Destructure the source differently than the target is shaped
+    pub(crate) shape: Option<SourceShape>,
+    // For enums, source variants that fail conversion with the given message
+    pub(crate) reject: Vec<(Ident, LitStr)>,
+    // Cross-field check on the fully built target
+    pub(crate) validate: Option<TypePath>,
+    // Replace the inferred where predicates for generic fields
+    pub(crate) bound: Option<Vec<WherePredicate>>,
+    // Generate `AsyncMogrify` instead of `TryFrom`, awaiting `parse` and `and_then`
+    pub(crate) asynchronous: bool,
+    // Generate `TryFromWith<Source, Ctx>` instead of `TryFrom`, passing `&Ctx` to `parse` and `and_then`
+    pub(crate) context: Option<Type>,
+    // Stop at the first failure instead of collecting every one
+    pub(crate) fail_fast: bool,
+    // Keep at most this many failures, only counting the rest
+    pub(crate) max_failures: Option<Expr>,
+    // Collect warnings into the context's `AsRef<Warnings>`, defaulting the context to `Warnings`
+    pub(crate) warnings: bool,
+    // The context was defaulted to `Warnings`, so a plain `TryFrom` discarding them is generated too
+    pub(crate) warnings_only: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SourceShape {
+    // `Source { a, b }`
+    Named,
+    // `Source(a, b)`, in target field order
+    Tuple,
 }
 
 pub(crate) struct MogrifyVariantAttrs {
     pub(crate) source: Option<Ident>,
+    // Absorb every source variant that isn't otherwise mapped
+    pub(crate) other: bool,
+    // Warn with this message whenever `other` absorbs a variant
+    pub(crate) warn: Option<LitStr>,
+    // Fail conversion of the mapped source variant with this message
+    pub(crate) reject: Option<LitStr>,
 }
 
 pub(crate) fn extract_mogrify_meta(attrs: Vec<Attribute>) -> Vec<Attribute> {
@@ -51,7 +167,27 @@ impl TryFrom<Vec<Attribute>> for MogrifyFieldAttrs {
             let mut default = None;
             let mut and_then = None;
             let mut parse = None;
+            let mut from = None;
+            let mut enumeration = None;
+            let mut context = false;
+            let mut validators = Vec::new();
+            let mut whole = Vec::new();
             list.parse_nested_meta(|meta| {
+                if let Some(validator) = parse_validator(&meta)? {
+                    validators.push(validator);
+                    return Ok(());
+                }
+                if meta.path.is_ident("whole") {
+                    return meta.parse_nested_meta(|meta| match parse_validator(&meta)? {
+                        Some(validator) => {
+                            whole.push(validator);
+                            Ok(())
+                        }
+                        None => Err(meta.error(
+                            r#"expected either "len(...)", "range(...)", "pattern = ...", or "non_empty""#,
+                        )),
+                    });
+                }
                 if meta.path.is_ident("require") {
                     require = true;
                     return Ok(());
@@ -78,7 +214,7 @@ impl TryFrom<Vec<Attribute>> for MogrifyFieldAttrs {
                 }
                 if meta.path.is_ident("parse") {
                     if meta.input.is_empty() {
-                        parse = Some(parse_quote!(::mogrify::util::force_parse))
+                        parse = Some(parse_quote!(::mogrify::util::mogrify_parse))
                     } else {
                         let value = meta.value()?;
                         let path: TypePath = value.parse()?;
@@ -86,16 +222,49 @@ impl TryFrom<Vec<Attribute>> for MogrifyFieldAttrs {
                     }
                     return Ok(());
                 }
+                if meta.path.is_ident("from") {
+                    let value = meta.value()?;
+                    let name: LitStr = value.parse()?;
+                    from = Some(name.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("enumeration") {
+                    let value = meta.value()?;
+                    let path: TypePath = value.parse()?;
+                    enumeration = Some(path);
+                    return Ok(());
+                }
+                if meta.path.is_ident("context") {
+                    context = true;
+                    return Ok(());
+                }
                 Err(meta.error(
-                    r#"expected either "require", "raw", "parse=...", "default = ...", or "and_then = ...""#,
+                    r#"expected either "require", "raw", "parse=...", "default = ...", "and_then = ...", "from = ...", "enumeration = ...", "context", "len(...)", "range(...)", "pattern = ...", "non_empty", or "whole(...)""#,
                 ))
             })?;
+            if let (Some(_), Some(enumeration)) = (&parse, &enumeration) {
+                return Err(Error::new_spanned(
+                    enumeration,
+                    r#""enumeration = ..." can't be combined with "parse""#,
+                ));
+            }
+            if context && (parse.is_some() || enumeration.is_some()) {
+                return Err(Error::new_spanned(
+                    attr,
+                    r#""context" can't be combined with "parse" or "enumeration""#,
+                ));
+            }
             Ok(MogrifyFieldAttrs {
                 require,
                 raw,
                 parse,
                 default,
                 and_then,
+                from,
+                enumeration,
+                context,
+                validators,
+                whole,
             })
         } else {
             Ok(MogrifyFieldAttrs {
@@ -104,6 +273,11 @@ impl TryFrom<Vec<Attribute>> for MogrifyFieldAttrs {
                 parse: None,
                 default: None,
                 and_then: None,
+                from: None,
+                enumeration: None,
+                context: false,
+                validators: Vec::new(),
+                whole: Vec::new(),
             })
         }
     }
@@ -114,9 +288,26 @@ impl TryFrom<Attribute> for MogrifyStructAttrs {
 
     fn try_from(value: Attribute) -> Result<Self, Self::Error> {
         value.parse_args_with(|input: ParseStream| {
-            // Source type is first argument, always
+            // Source type is first argument, always, optionally introducing its own generic params
+            let generics = if input.peek(Token![for]) {
+                input.parse::<Token![for]>()?;
+                input.parse::<Generics>()?
+            } else {
+                Generics::default()
+            };
             let source: TypePath = input.parse()?;
             let mut grpc = false;
+            let mut reverse = false;
+            let mut ignore_extra = false;
+            let mut shape = None;
+            let mut reject = Vec::new();
+            let mut validate = None;
+            let mut bound = None;
+            let mut asynchronous = false;
+            let mut context = None;
+            let mut fail_fast = false;
+            let mut max_failures = None;
+            let mut warnings = false;
 
             // from here on out, we're basically emulating `syn::meta::ParsedNestedMeta`, but without the "accept keywords in the path" logic
             // because for some reason `parse_meta_path` is not a public function.
@@ -125,16 +316,112 @@ impl TryFrom<Attribute> for MogrifyStructAttrs {
                 if input.is_empty() {
                     break;
                 }
+                // `async` is a keyword, so it can't be parsed as a path
+                if input.peek(Token![async]) {
+                    input.parse::<Token![async]>()?;
+                    asynchronous = true;
+                    continue;
+                }
                 let path = input.parse::<Path>()?;
 
                 if path.is_ident("grpc") {
                     grpc = true;
+                } else if path.is_ident("reverse") {
+                    reverse = true;
+                } else if path.is_ident("warnings") {
+                    warnings = true;
+                } else if path.is_ident("fail_fast") {
+                    fail_fast = true;
+                } else if path.is_ident("ignore_extra") {
+                    ignore_extra = true;
+                } else if path.is_ident("shape") {
+                    input.parse::<Token![=]>()?;
+                    let value = input.parse::<Ident>()?;
+                    if value == "named" {
+                        shape = Some(SourceShape::Named);
+                    } else if value == "tuple" {
+                        shape = Some(SourceShape::Tuple);
+                    } else {
+                        return Err(Error::new_spanned(
+                            value,
+                            r#"expected either "named" or "tuple""#,
+                        ));
+                    }
+                } else if path.is_ident("reject") {
+                    let content;
+                    parenthesized!(content in input);
+                    while !content.is_empty() {
+                        let variant = content.parse::<Ident>()?;
+                        content.parse::<Token![=]>()?;
+                        reject.push((variant, content.parse::<LitStr>()?));
+                        if content.is_empty() {
+                            break;
+                        }
+                        content.parse::<Token![,]>()?;
+                    }
+                } else if path.is_ident("validate") {
+                    input.parse::<Token![=]>()?;
+                    validate = Some(input.parse::<TypePath>()?);
+                } else if path.is_ident("bound") {
+                    input.parse::<Token![=]>()?;
+                    let predicates = input
+                        .parse::<LitStr>()?
+                        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+                    bound = Some(predicates.into_iter().collect());
+                } else if path.is_ident("context") {
+                    input.parse::<Token![=]>()?;
+                    context = Some(input.parse::<Type>()?);
+                } else if path.is_ident("max_failures") {
+                    input.parse::<Token![=]>()?;
+                    max_failures = Some(input.parse::<Expr>()?);
                 } else {
                     return Err(Error::new_spanned(path, "unrecognized argument"));
                 }
             }
 
-            Ok(MogrifyStructAttrs { source, grpc })
+            if let (true, Some(context)) = (asynchronous, &context) {
+                return Err(Error::new_spanned(
+                    context,
+                    r#""context = ..." can't be combined with "async""#,
+                ));
+            }
+
+            if asynchronous && fail_fast {
+                return Err(Error::new_spanned(
+                    &source,
+                    r#""fail_fast" can't be combined with "async""#,
+                ));
+            }
+
+            if asynchronous && warnings {
+                return Err(Error::new_spanned(
+                    &source,
+                    r#""warnings" can't be combined with "async""#,
+                ));
+            }
+
+            let warnings_only = warnings && context.is_none();
+            if warnings_only {
+                context = Some(parse_quote!(::mogrify::Warnings));
+            }
+
+            Ok(MogrifyStructAttrs {
+                source,
+                generics,
+                grpc,
+                reverse,
+                ignore_extra,
+                shape,
+                reject,
+                validate,
+                bound,
+                asynchronous,
+                context,
+                fail_fast,
+                max_failures,
+                warnings,
+                warnings_only,
+            })
         })
     }
 }
@@ -153,6 +440,9 @@ impl TryFrom<Vec<Attribute>> for MogrifyVariantAttrs {
             let value = &attr.meta;
             let list = value.require_list()?;
             let mut source = None;
+            let mut other = false;
+            let mut warn = None;
+            let mut reject = None;
             list.parse_nested_meta(|meta| {
                 if meta.path.is_ident("source") {
                     let value = meta.value()?;
@@ -160,11 +450,43 @@ impl TryFrom<Vec<Attribute>> for MogrifyVariantAttrs {
                     source = Some(ident);
                     return Ok(());
                 }
-                Err(meta.error(r#"expected "source=...""#))
+                if meta.path.is_ident("other") {
+                    other = true;
+                    return Ok(());
+                }
+                if meta.path.is_ident("warn") {
+                    warn = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                if meta.path.is_ident("reject") {
+                    reject = Some(meta.value()?.parse()?);
+                    return Ok(());
+                }
+                Err(meta
+                    .error(r#"expected one of "source=...", "other", "warn=..." or "reject=...""#))
             })?;
-            Ok(MogrifyVariantAttrs { source })
+            if let (Some(warn), false) = (&warn, other) {
+                return Err(Error::new_spanned(warn, r#""warn = ..." requires "other""#));
+            }
+            if let (Some(reject), true) = (&reject, other) {
+                return Err(Error::new_spanned(
+                    reject,
+                    r#""reject = ..." is not supported on the other variant"#,
+                ));
+            }
+            Ok(MogrifyVariantAttrs {
+                source,
+                other,
+                warn,
+                reject,
+            })
         } else {
-            Ok(MogrifyVariantAttrs { source: None })
+            Ok(MogrifyVariantAttrs {
+                source: None,
+                other: false,
+                warn: None,
+                reject: None,
+            })
         }
     }
 }
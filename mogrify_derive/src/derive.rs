@@ -1,15 +1,18 @@
-use crate::attrs::{extract_mogrify_meta, MogrifyStructAttrs, MogrifyVariantAttrs};
+use crate::attrs::{extract_mogrify_meta, MogrifyStructAttrs, MogrifyVariantAttrs, SourceShape};
 use crate::fields::MogrifyFieldInfo;
+use crate::generics::{merge_generics, ParamSubstitution};
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{
-    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, GenericArgument, PathArguments, Type,
-    TypePath,
+    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, GenericArgument, Generics, LitStr,
+    PathArguments, Type, TypeGenerics, TypePath,
 };
 
 pub(crate) fn derive_inner(input: DeriveInput) -> Result<TokenStream, Error> {
     let ident = input.ident;
     let ident_span = ident.span();
+    let generics = input.generics;
     let sources: Vec<MogrifyStructAttrs> = extract_mogrify_meta(input.attrs)
         .into_iter()
         .map(|attr| attr.try_into())
@@ -23,13 +26,13 @@ pub(crate) fn derive_inner(input: DeriveInput) -> Result<TokenStream, Error> {
     }
 
     match input.data {
-        Data::Struct(data) => derive_struct(ident, sources, data),
-        Data::Enum(data) => derive_enum(ident, sources, data),
+        Data::Struct(data) => derive_struct(ident, generics, sources, data),
+        Data::Enum(data) => derive_enum(ident, generics, sources, data),
         Data::Union(_) => Err(Error::new(ident_span, "Mogrify does not support unions")),
     }
 }
 
-fn turbofish_match_pattern(type_path: &TypePath) -> TokenStream {
+fn turbofish_match_pattern(type_path: &TypePath) -> Result<TokenStream, Error> {
     let path = &type_path.path;
     let segments = &path.segments;
 
@@ -42,47 +45,260 @@ fn turbofish_match_pattern(type_path: &TypePath) -> TokenStream {
         tokens.extend(quote! {#ident});
 
         if let PathArguments::AngleBracketed(ref args) = segment.arguments {
-            let args_tokens: Vec<TokenStream> = args
+            let args_tokens = args
                 .args
                 .iter()
                 .map(|arg| match arg {
-                    GenericArgument::Lifetime(lifetime) => {
-                        quote! {#lifetime}
-                    }
+                    GenericArgument::Lifetime(lifetime) => Ok(quote! {#lifetime}),
                     GenericArgument::Type(Type::Path(type_path)) => {
-                        let nested_tokens = turbofish_match_pattern(type_path);
-                        quote! {#nested_tokens}
-                    }
-                    GenericArgument::Const(constant) => {
-                        quote! {#constant}
+                        turbofish_match_pattern(type_path)
                     }
+                    // references, arrays, tuples and the like are valid as they are
+                    GenericArgument::Type(ty) => Ok(quote! {#ty}),
+                    GenericArgument::Const(constant) => Ok(quote! {#constant}),
                     GenericArgument::AssocType(assoc) => {
                         let ident = &assoc.ident;
                         let ty = &assoc.ty;
-                        quote! {#ident = #ty}
+                        Ok(quote! {#ident = #ty})
                     }
                     GenericArgument::AssocConst(assoc) => {
                         let ident = &assoc.ident;
                         let ty = &assoc.value;
-                        quote! {#ident = #ty}
+                        Ok(quote! {#ident = #ty})
                     }
                     GenericArgument::Constraint(constraint) => {
                         let ident = &constraint.ident;
                         let bounds = &constraint.bounds;
-                        quote! {#ident: #bounds}
+                        Ok(quote! {#ident: #bounds})
                     }
-                    _ => panic!("Unexpected generic argument"),
+                    arg => Err(Error::new_spanned(arg, "unsupported generic argument")),
                 })
-                .collect();
+                .collect::<Result<Vec<_>, _>>()?;
 
             tokens.extend(quote! {::<#(#args_tokens),*>});
         }
     }
-    tokens
+    Ok(tokens)
+}
+
+fn validate_instr(source: &MogrifyStructAttrs) -> Option<TokenStream> {
+    source.validate.as_ref().map(|validate| match source.context {
+        None => quote!(#validate(&value).map_err(::mogrify::MogrificationError::wrapping)?;),
+        Some(_) => quote!(::mogrify::util::with_context(&#validate, &value, ctx).map_err(::mogrify::MogrificationError::wrapping)?;),
+    })
+}
+
+/// Converts every field into a local `Option`, collecting failures into `errors`, or returning the first with `fail_fast`.
+/// Async conversions are all awaited together, so independent lookups run concurrently.
+fn capture_instr(
+    fields: &[MogrifyFieldInfo],
+    source: &MogrifyStructAttrs,
+) -> Result<TokenStream, Error> {
+    if let (None, Some(field)) = (&source.context, fields.iter().find(|f| f.attrs.context)) {
+        return Err(Error::new_spanned(
+            &field.ty,
+            r#""context" requires "context = ..." on the source"#,
+        ));
+    }
+    let locals = fields
+        .iter()
+        .map(|field| &field.local_ident)
+        .collect::<Vec<_>>();
+    if source.asynchronous {
+        let pending = fields
+            .iter()
+            .map(|field| field.async_conversion(fields.len()));
+        Ok(quote! {
+            #(let mut #locals = ::mogrify::util::Pending::new(#pending);)*
+            ::mogrify::util::join_pending(&mut [#(&mut #locals),*]).await;
+            #(let #locals = ::mogrify::util::capture_error(&mut errors, #locals.take());)*
+        })
+    } else if source.fail_fast {
+        let mogrify = fields
+            .iter()
+            .map(|field| field.conversion(fields.len(), source));
+        Ok(quote! {
+            #(let #locals = ::std::option::Option::Some(#mogrify?);)*
+        })
+    } else {
+        let mogrify = fields
+            .iter()
+            .map(|field| field.conversion(fields.len(), source));
+        Ok(quote! {
+            #(let #locals = ::mogrify::util::capture_error(&mut errors, #mogrify);)*
+        })
+    }
+}
+
+/// Wraps the conversion body in a `TryFrom`, `AsyncMogrify` or `TryFromWith` impl
+fn conversion_impl(
+    ident: &Ident,
+    ty_generics: &TypeGenerics,
+    attr: &MogrifyStructAttrs,
+    generics: &Generics,
+    body: TokenStream,
+) -> TokenStream {
+    let source = &attr.source;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let max_failures = match &attr.max_failures {
+        None => quote!(::std::primitive::usize::MAX),
+        Some(max_failures) => quote!(#max_failures),
+    };
+    let fail_fast = attr.fail_fast;
+    let warnings = if attr.warnings {
+        quote!(::std::option::Option::Some(warnings))
+    } else {
+        quote!(::std::option::Option::None)
+    };
+    let scope = quote! {
+        // never pushed to with `fail_fast`
+        #[allow(unused_mut)]
+        let mut errors = ::mogrify::MogrificationError::capped(#max_failures);
+        // only used by collection fields
+        #[allow(unused_variables)]
+        let scope = ::mogrify::util::Scope {
+            max_failures: #max_failures,
+            fail_fast: #fail_fast,
+            warnings: #warnings,
+        };
+    };
+    if let Some(context) = &attr.context {
+        let warnings = attr.warnings.then(
+            || quote!(let warnings: &::mogrify::Warnings = ::std::convert::AsRef::as_ref(ctx);),
+        );
+        // without a context of its own, plain conversions just drop the warnings
+        let try_from = attr.warnings_only.then(|| {
+            quote! {
+                impl #impl_generics TryFrom<#source> for #ident #ty_generics #where_clause {
+                    type Error = ::mogrify::MogrificationError;
+
+                    fn try_from(from: #source) -> Result<Self, Self::Error> {
+                        ::mogrify::TryFromWith::try_from_with(from, &::mogrify::Warnings::new())
+                    }
+                }
+            }
+        });
+        quote! {
+            impl #impl_generics ::mogrify::TryFromWith<#source, #context> for #ident #ty_generics #where_clause {
+                // not every conversion needs the context
+                #[allow(unused_variables)]
+                fn try_from_with(from: #source, ctx: &#context) -> Result<Self, ::mogrify::MogrificationError> {
+                    use ::mogrify::Pathed;
+                    #warnings
+                    #scope
+
+                    #body
+                }
+            }
+            #try_from
+        }
+    } else if attr.asynchronous {
+        quote! {
+            impl #impl_generics ::mogrify::AsyncMogrify<#source> for #ident #ty_generics #where_clause {
+                async fn mogrify_from(from: #source) -> Result<Self, ::mogrify::MogrificationError> {
+                    use ::mogrify::Pathed;
+                    #scope
+
+                    #body
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics TryFrom<#source> for #ident #ty_generics #where_clause {
+                type Error = ::mogrify::MogrificationError;
+
+                fn try_from(from: #source) -> Result<Self, Self::Error> {
+                    use ::mogrify::Pathed;
+                    #scope
+
+                    #body
+                }
+            }
+        }
+    }
+}
+
+/// Applies the source's `shape` override to which source field each target field reads from
+fn reshape_source(
+    fields: &[MogrifyFieldInfo],
+    source: &MogrifyStructAttrs,
+) -> Result<Vec<MogrifyFieldInfo>, Error> {
+    let mut fields = fields.to_vec();
+    match source.shape {
+        None => {}
+        Some(SourceShape::Named) if fields.iter().any(|f| f.source_ident.is_none()) => {
+            return Err(Error::new_spanned(
+                &source.source,
+                r#"named sources require every unnamed field to specify "from = ...""#,
+            ));
+        }
+        Some(SourceShape::Named) => {}
+        Some(SourceShape::Tuple) => {
+            if fields.iter().any(|f| f.attrs.from.is_some()) {
+                return Err(Error::new_spanned(
+                    &source.source,
+                    r#""from = ..." is not supported for tuple sources"#,
+                ));
+            }
+            for field in fields.iter_mut() {
+                field.source_ident = None;
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// Fills in ignored source fields from `Default` when reversing
+fn reverse_rest(
+    source: &MogrifyStructAttrs,
+    fields: &[MogrifyFieldInfo],
+) -> Result<Option<TokenStream>, Error> {
+    if !source.ignore_extra {
+        Ok(None)
+    } else if fields.iter().all(|f| f.source_ident.is_some()) {
+        Ok(Some(quote!(..::std::default::Default::default())))
+    } else {
+        Err(Error::new_spanned(
+            &source.source,
+            "reverse with ignore_extra requires named source fields",
+        ))
+    }
+}
+
+/// Wraps field expressions in braces or parens depending on whether the source fields are named.
+/// Spanned to the source type so that rustc reports unhandled source fields against it.
+fn source_group(
+    fields: &[MogrifyFieldInfo],
+    exprs: Vec<TokenStream>,
+    rest: Option<TokenStream>,
+    span: Span,
+) -> Result<TokenStream, Error> {
+    let named = fields.iter().filter(|f| f.source_ident.is_some()).count();
+    if named == fields.len() {
+        Ok(quote_spanned!(span=> { #(#exprs,)* #rest }))
+    } else if named == 0 {
+        Ok(quote_spanned!(span=> ( #(#exprs,)* #rest )))
+    } else {
+        Err(Error::new(
+            Span::call_site(),
+            r#"either all or none of the unnamed fields must specify "from = ...""#,
+        ))
+    }
+}
+
+/// Wraps field expressions in braces or parens depending on whether the target fields are named
+fn target_group(fields: &[MogrifyFieldInfo], exprs: Vec<TokenStream>) -> TokenStream {
+    if fields.iter().all(|f| f.target_ident.is_some()) {
+        quote!({ #(#exprs),* })
+    } else {
+        quote!(( #(#exprs),* ))
+    }
 }
 
 pub(crate) fn derive_struct(
     ident: Ident,
+    generics: Generics,
     sources: Vec<MogrifyStructAttrs>,
     data: DataStruct,
 ) -> Result<TokenStream, Error> {
@@ -93,63 +309,126 @@ pub(crate) fn derive_struct(
         .map(|f| f.try_into())
         .collect::<Result<Vec<MogrifyFieldInfo>, _>>()?;
 
-    let destructure_instr = fields
-        .iter()
-        .map(|field| field.destructure_expr())
-        .collect::<Vec<_>>();
+    let assign_instr = target_group(
+        &fields,
+        fields.iter().map(|field| field.assignment_expr()).collect(),
+    );
 
-    let capture_instr = fields
-        .iter()
-        .map(|field| {
-            let local_ident = &field.local_ident;
-            let mogrify = field.conversion(fields.len());
-            quote!(let #local_ident = ::mogrify::util::capture_error(&mut errors, #mogrify);)
-        })
-        .collect::<Vec<_>>();
-
-    let assign_instr = fields
-        .iter()
-        .map(|field| field.assignment_expr())
-        .collect::<Vec<_>>();
+    let reverse_destructure_instr = target_group(
+        &fields,
+        fields
+            .iter()
+            .map(|field| field.reverse_destructure_expr())
+            .collect(),
+    );
 
     let mut tokens = TokenStream::new();
 
-    for MogrifyStructAttrs { source, grpc: _ } in sources {
-        let match_expr = turbofish_match_pattern(&source);
-        tokens.extend(quote! {
-            impl TryFrom<#source> for #ident {
-                type Error = ::mogrify::MogrificationError;
+    let (_, ty_generics, _) = generics.split_for_impl();
 
-                fn try_from(from: #source) -> Result<Self, Self::Error> {
-                    use ::mogrify::Pathed;
-                    let mut errors = ::std::vec::Vec::<::mogrify::MogrificationError>::new();
-
-                    let #match_expr { #(#destructure_instr),* } = from;
-
-                    #(#capture_instr)*
-
-                    ::mogrify::MogrificationError::condense(errors)?;
-                    Ok(Self {
-                        #(#assign_instr),*
-                    })
+    for attr in sources {
+        let source = &attr.source;
+        let match_expr = turbofish_match_pattern(source)?;
+        let fields = reshape_source(&fields, &attr)?;
+        let capture_instr = capture_instr(&fields, &attr)?;
+        let destructure_instr = source_group(
+            &fields,
+            fields
+                .iter()
+                .map(|field| field.destructure_expr())
+                .collect(),
+            attr.ignore_extra.then(|| quote!(..)),
+            source.span(),
+        )?;
+        let validate_instr = validate_instr(&attr);
+        let mut substitution = ParamSubstitution::new(&generics, &attr);
+        let try_from_generics =
+            merge_generics(&generics, &attr, substitution.try_from_bounds(&fields));
+        tokens.extend(conversion_impl(
+            &ident,
+            &ty_generics,
+            &attr,
+            &try_from_generics,
+            quote! {
+                let #match_expr #destructure_instr = from;
+
+                #capture_instr
+
+                errors.into_result()?;
+                let value = Self #assign_instr;
+                #validate_instr
+                Ok(value)
+            },
+        ));
+        if attr.reverse {
+            let reverse_assign_instr = source_group(
+                &fields,
+                fields
+                    .iter()
+                    .map(|field| field.reverse_assignment_expr())
+                    .collect::<Result<_, _>>()?,
+                reverse_rest(&attr, &fields)?,
+                source.span(),
+            )?;
+            let from_generics =
+                merge_generics(&generics, &attr, substitution.reverse_bounds(&fields));
+            let (impl_generics, _, where_clause) = from_generics.split_for_impl();
+            tokens.extend(quote! {
+                impl #impl_generics From<#ident #ty_generics> for #source #where_clause {
+                    fn from(from: #ident #ty_generics) -> Self {
+                        let #ident #reverse_destructure_instr = from;
+
+                        #match_expr #reverse_assign_instr
+                    }
                 }
-            }
-        });
+            });
+        }
     }
     Ok(tokens)
 }
 
 fn build_match_instr(
-    source: MogrifyStructAttrs,
+    source: &MogrifyStructAttrs,
     data: DataEnum,
 ) -> Result<Vec<TokenStream>, Error> {
     let mut variant_matches = Vec::<TokenStream>::new();
+    let source_span = source.source.span();
+    let source_match_expr = turbofish_match_pattern(&source.source)?;
+    let mut other = None;
 
     for variant in data.variants {
         let variant_attrs: MogrifyVariantAttrs = extract_mogrify_meta(variant.attrs).try_into()?;
+        if variant_attrs.other {
+            if !matches!(variant.fields, Fields::Unit) {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "other is only supported on unit variants",
+                ));
+            }
+            if other.is_some() {
+                return Err(Error::new_spanned(
+                    &variant.ident,
+                    "only one variant can be marked as other",
+                ));
+            }
+            if let (Some(warn), false) = (&variant_attrs.warn, source.warnings) {
+                return Err(Error::new_spanned(
+                    warn,
+                    r#""warn = ..." requires "warnings" on the source"#,
+                ));
+            }
+            other = Some((variant.ident.clone(), variant_attrs.warn.clone()));
+            if variant_attrs.source.is_none() {
+                continue;
+            }
+        }
         let source_name = variant_attrs
             .source
             .unwrap_or_else(|| variant.ident.clone());
+        if let Some(message) = &variant_attrs.reject {
+            variant_matches.push(reject_instr(&source_name, message));
+            continue;
+        }
         let variant_name = &variant.ident;
         match variant.fields {
             Fields::Named(fields) => {
@@ -159,35 +438,31 @@ fn build_match_instr(
                     .enumerate()
                     .map(|f| f.try_into())
                     .collect::<Result<Vec<MogrifyFieldInfo>, _>>()?;
-                let destructure_instr = fields
-                    .iter()
-                    .map(|field| field.destructure_expr())
-                    .collect::<Vec<_>>();
-
-                let capture_instr = fields
-                    .iter()
-                    .map(|field| {
-                        let local_ident = &field.local_ident;
-                        let mogrify = field.conversion(fields.len());
-                        quote!(let #local_ident = ::mogrify::util::capture_error(&mut errors, #mogrify);)
-                    })
-                    .collect::<Vec<_>>();
-
-                let assign_instr = fields
-                    .iter()
-                    .map(|field| field.assignment_expr())
-                    .collect::<Vec<_>>();
-
-                let source_name_string = source_name.to_string();
-                variant_matches.push(quote! {
-                    #source_name { #(#destructure_instr),* } => {
-                        #(#capture_instr)*
-                        ::mogrify::MogrificationError::condense(errors).at_field(#source_name_string)?;
-                        Self::#variant_name {
-                            #(#assign_instr),*
-                        }
-                    }
-                })
+                let fields = reshape_source(&fields, source)?;
+                let destructure_instr = source_group(
+                    &fields,
+                    fields
+                        .iter()
+                        .map(|field| field.destructure_expr())
+                        .collect(),
+                    source.ignore_extra.then(|| quote!(..)),
+                    source_span,
+                )?;
+
+                let capture_instr = capture_instr(&fields, source)?;
+
+                let assign_instr = target_group(
+                    &fields,
+                    fields.iter().map(|field| field.assignment_expr()).collect(),
+                );
+
+                variant_matches.push(variant_arm(
+                    source,
+                    &source_name,
+                    destructure_instr,
+                    capture_instr,
+                    quote!(Self::#variant_name #assign_instr),
+                ))
             }
             Fields::Unnamed(fields) => {
                 let fields = fields
@@ -196,45 +471,185 @@ fn build_match_instr(
                     .enumerate()
                     .map(|f| f.try_into())
                     .collect::<Result<Vec<MogrifyFieldInfo>, _>>()?;
+                let fields = reshape_source(&fields, source)?;
+
+                let destructure_instr = source_group(
+                    &fields,
+                    fields
+                        .iter()
+                        .map(|field| field.destructure_expr())
+                        .collect(),
+                    source.ignore_extra.then(|| quote!(..)),
+                    source_span,
+                )?;
+
+                let capture_instr = capture_instr(&fields, source)?;
+
+                let assign_instr = target_group(
+                    &fields,
+                    fields.iter().map(|field| field.assignment_expr()).collect(),
+                );
+
+                variant_matches.push(variant_arm(
+                    source,
+                    &source_name,
+                    destructure_instr,
+                    capture_instr,
+                    quote!(Self::#variant_name #assign_instr),
+                ))
+            }
+            Fields::Unit => {
+                // the source path prefix is added to every match below
+                if source.grpc {
+                    variant_matches.push(quote! {
+                        #source_name(()) => Self::#variant_name
+                    })
+                } else {
+                    variant_matches.push(quote! {
+                        #source_name => Self::#variant_name
+                    })
+                }
+            }
+        }
+    }
+    for (source_name, message) in &source.reject {
+        variant_matches.push(reject_instr(source_name, message));
+    }
+    let mut variant_matches = variant_matches
+        .into_iter()
+        .map(|variant_match| quote!(#source_match_expr :: #variant_match))
+        .collect::<Vec<_>>();
+    match other {
+        Some((other, None)) => variant_matches.push(quote! {
+            #[allow(unreachable_patterns)]
+            _ => Self::#other
+        }),
+        Some((other, Some(warn))) => variant_matches.push(quote! {
+            #[allow(unreachable_patterns)]
+            _ => {
+                warnings.warn(::mogrify::MogrificationError::new_coded(::mogrify::FailureCode::UnknownVariant, #warn));
+                Self::#other
+            }
+        }),
+        None => {}
+    }
+    Ok(variant_matches)
+}
 
-                let destructure_instr = fields
-                    .iter()
-                    .map(|field| field.destructure_expr())
-                    .collect::<Vec<_>>();
+/// A match arm converting the fields of a source variant, pathing their failures and warnings by it
+fn variant_arm(
+    source: &MogrifyStructAttrs,
+    source_name: &Ident,
+    destructure_instr: TokenStream,
+    capture_instr: TokenStream,
+    assign_instr: TokenStream,
+) -> TokenStream {
+    let source_name_string = source_name.to_string();
+    let mark = source.warnings.then(|| quote!(let mark = warnings.mark();));
+    let mark_variant = source
+        .warnings
+        .then(|| quote!(mark.at_variant(#source_name_string);));
+    if source.fail_fast {
+        // `fail_fast` returns from the first failing field, so the fields convert in a closure
+        // to still path that failure by the variant
+        quote! {
+            #source_name #destructure_instr => {
+                #mark
+                let result = (|| -> ::std::result::Result<Self, ::mogrify::MogrificationError> {
+                    #capture_instr
+                    ::std::result::Result::Ok(#assign_instr)
+                })();
+                #mark_variant
+                result.at_variant(#source_name_string)?
+            }
+        }
+    } else if source.warnings {
+        quote! {
+            #source_name #destructure_instr => {
+                #mark
+                #capture_instr
+                errors.into_result().at_variant(#source_name_string)?;
+                #mark_variant
+                #assign_instr
+            }
+        }
+    } else {
+        quote! {
+            #source_name #destructure_instr => {
+                #capture_instr
+                errors.into_result().at_variant(#source_name_string)?;
+                #assign_instr
+            }
+        }
+    }
+}
 
-                let capture_instr = fields
-                    .iter()
-                    .map(|field| {
-                        let local_ident = &field.local_ident;
-                        let mogrify = field.conversion(fields.len());
-                        quote!(let #local_ident = ::mogrify::util::capture_error(&mut errors, #mogrify);)
-                    })
-                    .collect::<Vec<_>>();
+/// A match arm failing conversion of the source variant, whatever its fields
+fn reject_instr(source_name: &Ident, message: &LitStr) -> TokenStream {
+    let source_name_string = source_name.to_string();
+    quote! {
+        #source_name { .. } => return Err(::mogrify::MogrificationError::new_coded(::mogrify::FailureCode::Rejected, #message).at_variant(#source_name_string))
+    }
+}
 
-                let assign_instr = fields
-                    .iter()
-                    .map(|field| field.assignment_expr())
-                    .collect::<Vec<_>>();
+fn build_reverse_match_instr(
+    ident: &Ident,
+    source: &MogrifyStructAttrs,
+    data: DataEnum,
+) -> Result<Vec<TokenStream>, Error> {
+    let mut variant_matches = Vec::<TokenStream>::new();
+    let source_match_expr = turbofish_match_pattern(&source.source)?;
+
+    for variant in data.variants {
+        let variant_attrs: MogrifyVariantAttrs = extract_mogrify_meta(variant.attrs).try_into()?;
+        if variant_attrs.other && variant_attrs.source.is_none() {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                r#"reverse requires "source = ..." on the other variant"#,
+            ));
+        }
+        let source_name = variant_attrs
+            .source
+            .unwrap_or_else(|| variant.ident.clone());
+        let variant_name = &variant.ident;
+        match variant.fields {
+            Fields::Named(_) | Fields::Unnamed(_) => {
+                let fields = variant
+                    .fields
+                    .into_iter()
+                    .enumerate()
+                    .map(|f| f.try_into())
+                    .collect::<Result<Vec<MogrifyFieldInfo>, _>>()?;
+                let fields = reshape_source(&fields, source)?;
+                let destructure_instr = target_group(
+                    &fields,
+                    fields
+                        .iter()
+                        .map(|field| field.reverse_destructure_expr())
+                        .collect(),
+                );
+                let assign_instr = source_group(
+                    &fields,
+                    fields
+                        .iter()
+                        .map(|field| field.reverse_assignment_expr())
+                        .collect::<Result<_, _>>()?,
+                    None,
+                    source.source.span(),
+                )?;
 
                 variant_matches.push(quote! {
-                    #source_name ( #(#destructure_instr),* ) => {
-                        #(#capture_instr)*
-                        ::mogrify::MogrificationError::condense(errors)?;
-                        Self::#variant_name (
-                            #(#assign_instr),*
-                        )
-                    }
+                    #ident::#variant_name #destructure_instr => #source_match_expr :: #source_name #assign_instr
                 })
             }
             Fields::Unit => {
-                // can use source_name directly because we'll bring all variants in scope in the try_from body
                 if source.grpc {
                     variant_matches.push(quote! {
-                        #source_name(()) => Self::#variant_name
+                        #ident::#variant_name => #source_match_expr :: #source_name(())
                     })
                 } else {
                     variant_matches.push(quote! {
-                        #source_name => Self::#variant_name
+                        #ident::#variant_name => #source_match_expr :: #source_name
                     })
                 }
             }
@@ -245,29 +660,69 @@ fn build_match_instr(
 
 pub(crate) fn derive_enum(
     ident: Ident,
+    generics: Generics,
     attrs: Vec<MogrifyStructAttrs>,
     data: DataEnum,
 ) -> Result<TokenStream, Error> {
     let mut tokens = TokenStream::new();
+    let (_, ty_generics, _) = generics.split_for_impl();
+
+    // every variant's fields, for inferring bounds on generic params
+    let fields = data
+        .variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter().cloned().enumerate())
+        .map(|f| f.try_into())
+        .collect::<Result<Vec<MogrifyFieldInfo>, _>>()?;
 
     for attr in attrs {
-        let match_expr = turbofish_match_pattern(&attr.source);
         let source = attr.source.clone();
-        let variant_matches = build_match_instr(attr, data.clone())?;
-        tokens.extend(quote! {
-            impl TryFrom<#source> for #ident {
-                type Error = ::mogrify::MogrificationError;
-
-                fn try_from(from: #source) -> Result<Self, Self::Error> {
-                    use ::mogrify::Pathed;
-                    let mut errors = ::std::vec::Vec::<::mogrify::MogrificationError>::new();
-
-                    Ok(match from {
-                        #(#match_expr :: #variant_matches),*
-                    })
-                }
+        let validate_instr = validate_instr(&attr);
+        let mut substitution = ParamSubstitution::new(&generics, &attr);
+        let try_from_generics =
+            merge_generics(&generics, &attr, substitution.try_from_bounds(&fields));
+        let variant_matches = build_match_instr(&attr, data.clone())?;
+        let reverse = if attr.reverse {
+            if attr.ignore_extra {
+                return Err(Error::new_spanned(
+                    &attr.source,
+                    "reverse with ignore_extra is not supported for enums",
+                ));
             }
-        });
+            let from_generics =
+                merge_generics(&generics, &attr, substitution.reverse_bounds(&fields));
+            Some((
+                from_generics,
+                build_reverse_match_instr(&ident, &attr, data.clone())?,
+            ))
+        } else {
+            None
+        };
+        tokens.extend(conversion_impl(
+            &ident,
+            &ty_generics,
+            &attr,
+            &try_from_generics,
+            quote! {
+                let value = match from {
+                    #(#variant_matches),*
+                };
+                #validate_instr
+                Ok(value)
+            },
+        ));
+        if let Some((from_generics, reverse_matches)) = reverse {
+            let (impl_generics, _, where_clause) = from_generics.split_for_impl();
+            tokens.extend(quote! {
+                impl #impl_generics From<#ident #ty_generics> for #source #where_clause {
+                    fn from(from: #ident #ty_generics) -> Self {
+                        match from {
+                            #(#reverse_matches),*
+                        }
+                    }
+                }
+            });
+        }
     }
     Ok(tokens)
 }
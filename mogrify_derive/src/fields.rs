@@ -1,32 +1,353 @@
-use crate::attrs::{extract_mogrify_meta, MogrifyFieldAttrs};
+use crate::attrs::{extract_mogrify_meta, MogrifyFieldAttrs, MogrifyStructAttrs, MogrifyValidator};
 use proc_macro2::Span;
-use quote::{quote, TokenStreamExt};
-use syn::{Field, GenericArgument, Ident, PathArguments, Type, TypePath};
+use quote::{format_ident, quote, TokenStreamExt};
+use syn::{Expr, Field, GenericArgument, Ident, PathArguments, Type, TypePath};
 
+#[derive(Clone)]
 pub(crate) struct MogrifyFieldInfo {
     pub(crate) idx: usize,
     pub(crate) local_ident: Ident,
+    // Name of the field on the source, which differs from `target_ident` when renamed via `from = "..."`
     pub(crate) source_ident: Option<Ident>,
+    pub(crate) target_ident: Option<Ident>,
+    pub(crate) ty: Type,
     pub(crate) attrs: MogrifyFieldAttrs,
     pub(crate) specialization: MogrifyFieldSpecialization,
 }
 
-#[allow(dead_code)]
+// Recursive, so that nested containers like `Option<Vec<T>>` convert element by element at every level
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum MogrifyFieldSpecialization {
     None {
         ty: Type,
     },
     Option {
-        ty: GenericArgument,
+        ty: Type,
+        inner: Box<MogrifyFieldSpecialization>,
     },
-    Vec {
-        ty: GenericArgument,
+    Box {
+        ty: Type,
+        inner: Box<MogrifyFieldSpecialization>,
+    },
+    Sequence {
+        ty: Type,
+        collection: MogrifyCollection,
+        inner: Box<MogrifyFieldSpecialization>,
     },
     Map {
-        key_ty: GenericArgument,
-        value_ty: GenericArgument,
+        ty: Type,
+        collection: MogrifyCollection,
+        key_ty: Type,
+        value: Box<MogrifyFieldSpecialization>,
     },
 }
+
+// The conversion applied to the innermost element
+struct MogrifyLeaf {
+    parse: Option<proc_macro2::TokenStream>,
+    // built-in validators, run before `and_then`
+    checks: Vec<proc_macro2::TokenStream>,
+    and_then: Option<proc_macro2::TokenStream>,
+}
+
+impl MogrifyLeaf {
+    // the `with` function for a `mogrify_*_with` helper, or `None` for plain `TryFrom`
+    fn with_fn(&self) -> Option<proc_macro2::TokenStream> {
+        let Self {
+            parse,
+            checks,
+            and_then,
+        } = self;
+        if checks.is_empty() && and_then.is_none() {
+            return parse.clone();
+        }
+        let base = match parse {
+            None => quote!(::mogrify::util::mogrify_raw(value)),
+            Some(parse) => quote!(::mogrify::util::mogrify_raw_with(#parse, value)),
+        };
+        let and_then = and_then.as_ref().map(|and_then| {
+            quote!(.and_then(|r| (#and_then)(r).map_err(::mogrify::MogrificationError::wrapping)))
+        });
+        Some(quote!(|value| #base #(.and_then(#checks))* #and_then))
+    }
+    // the `with` function for a `mogrify_*_async` helper, where `parse` and `and_then` return futures
+    fn async_with_fn(&self) -> proc_macro2::TokenStream {
+        let Self {
+            parse,
+            checks,
+            and_then,
+        } = self;
+        let parse = parse.clone().unwrap_or_else(|| {
+            quote!(|value| ::std::future::ready(::mogrify::util::mogrify_raw(value)))
+        });
+        match and_then {
+            None if checks.is_empty() => parse,
+            None => quote! {
+                |value| async move {
+                    ::mogrify::util::mogrify_raw_async(#parse, value).await #(.and_then(#checks))*
+                }
+            },
+            Some(and_then) => quote! {
+                |value| async move {
+                    match ::mogrify::util::mogrify_raw_async(#parse, value).await #(.and_then(#checks))* {
+                        Ok(r) => (#and_then)(r).await.map_err(::mogrify::MogrificationError::wrapping),
+                        Err(err) => Err(err),
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl MogrifyValidator {
+    // a function checking a converted value and handing it back
+    fn check(&self) -> proc_macro2::TokenStream {
+        let bound = |bound: &Option<Expr>| match bound {
+            None => quote!(::std::option::Option::None),
+            Some(bound) => quote!(::std::option::Option::Some(#bound)),
+        };
+        match self {
+            MogrifyValidator::Len { min, max } => {
+                let (min, max) = (bound(min), bound(max));
+                quote!(|value| ::mogrify::validate::len(value, #min, #max))
+            }
+            MogrifyValidator::Range { min, max } => {
+                let (min, max) = (bound(min), bound(max));
+                quote!(|value| ::mogrify::validate::range(value, #min, #max))
+            }
+            MogrifyValidator::Pattern(pattern) => quote! {
+                |value| {
+                    // compiled on first use, then shared by every conversion
+                    static PATTERN: ::mogrify::validate::Pattern = ::mogrify::validate::Pattern::new(#pattern);
+                    ::mogrify::validate::pattern(value, &PATTERN)
+                }
+            },
+            MogrifyValidator::NonEmpty => quote!(::mogrify::validate::non_empty),
+        }
+    }
+}
+
+impl MogrifyFieldSpecialization {
+    fn from_type(ty: Type) -> Result<MogrifyFieldSpecialization, syn::Error> {
+        let Type::Path(path) = &ty else {
+            return Ok(MogrifyFieldSpecialization::None { ty });
+        };
+        let specialization = if type_shape_check(path, "Option", 1) {
+            match extract_single_generic(path)? {
+                GenericArgument::Type(inner) => MogrifyFieldSpecialization::Option {
+                    inner: Box::new(Self::from_type(inner)?),
+                    ty,
+                },
+                _ => MogrifyFieldSpecialization::None { ty },
+            }
+        } else if type_shape_check(path, "Box", 1) {
+            match extract_single_generic(path)? {
+                // `str`, slices and trait objects can't be moved out of their box
+                GenericArgument::Type(inner) if is_sized_path(&inner) => {
+                    MogrifyFieldSpecialization::Box {
+                        inner: Box::new(Self::from_type(inner)?),
+                        ty,
+                    }
+                }
+                _ => MogrifyFieldSpecialization::None { ty },
+            }
+        } else if let Some((_, collection)) = SEQUENCES
+            .into_iter()
+            .find(|(name, _)| type_shape_check(path, name, 1))
+        {
+            match extract_single_generic(path)? {
+                GenericArgument::Type(inner) => MogrifyFieldSpecialization::Sequence {
+                    collection,
+                    inner: Box::new(Self::from_type(inner)?),
+                    ty,
+                },
+                _ => MogrifyFieldSpecialization::None { ty },
+            }
+        } else if let Some((_, collection)) = MAPS
+            .into_iter()
+            .find(|(name, _)| type_shape_check(path, name, 2))
+        {
+            match extract_double_generic(path)? {
+                (GenericArgument::Type(key_ty), GenericArgument::Type(value_ty)) => {
+                    MogrifyFieldSpecialization::Map {
+                        collection,
+                        key_ty,
+                        value: Box::new(Self::from_type(value_ty)?),
+                        ty,
+                    }
+                }
+                _ => MogrifyFieldSpecialization::None { ty },
+            }
+        } else {
+            MogrifyFieldSpecialization::None { ty }
+        };
+        Ok(specialization)
+    }
+    pub(crate) fn ty(&self) -> &Type {
+        match self {
+            MogrifyFieldSpecialization::None { ty }
+            | MogrifyFieldSpecialization::Option { ty, .. }
+            | MogrifyFieldSpecialization::Box { ty, .. }
+            | MogrifyFieldSpecialization::Sequence { ty, .. }
+            | MogrifyFieldSpecialization::Map { ty, .. } => ty,
+        }
+    }
+    // suffix of the `::mogrify::util::mogrify_*` helpers and the nested specialization, if any
+    fn helper(&self) -> Option<(&'static str, &MogrifyFieldSpecialization)> {
+        match self {
+            MogrifyFieldSpecialization::None { .. } => None,
+            MogrifyFieldSpecialization::Option { inner, .. } => Some(("opt", inner)),
+            MogrifyFieldSpecialization::Box { inner, .. } => Some(("box", inner)),
+            MogrifyFieldSpecialization::Sequence { inner, .. } => Some(("seq", inner)),
+            MogrifyFieldSpecialization::Map { value, .. } => Some(("pairs", value)),
+        }
+    }
+    // a function converting the whole value at this level
+    fn converter(&self, leaf: &MogrifyLeaf) -> proc_macro2::TokenStream {
+        let (helper, inner) = match self.helper() {
+            None => {
+                return match leaf.with_fn() {
+                    None => quote!(::mogrify::util::mogrify_raw),
+                    Some(with) => {
+                        quote!(|value| ::mogrify::util::mogrify_raw_with(#with, value))
+                    }
+                }
+            }
+            Some(helper) => helper,
+        };
+        let with = inner.element_fn(leaf);
+        match (self.collection_generics(quote!()), with) {
+            // collections take the conversion's `scope`, for its failure cap and `fail_fast`
+            (Some(generics), with) => {
+                let with = with.unwrap_or_else(|| quote!(::mogrify::util::mogrify_raw));
+                let helper = format_ident!("mogrify_{}_in", helper);
+                quote!(|value| ::mogrify::util::#helper::<#generics>(scope, #with, value))
+            }
+            (_, None) => {
+                let helper = format_ident!("mogrify_{}", helper);
+                quote!(::mogrify::util::#helper)
+            }
+            (_, Some(with)) => {
+                let helper = format_ident!("mogrify_{}_with", helper);
+                quote!(|value| ::mogrify::util::#helper(#with, value))
+            }
+        }
+    }
+    // a function converting each element of the enclosing container, or `None` for plain `TryFrom`
+    fn element_fn(&self, leaf: &MogrifyLeaf) -> Option<proc_macro2::TokenStream> {
+        match self {
+            MogrifyFieldSpecialization::None { .. } => leaf.with_fn(),
+            _ => Some(self.converter(leaf)),
+        }
+    }
+    // a function converting the whole value at this level into a future
+    fn async_converter(&self, leaf: &MogrifyLeaf) -> proc_macro2::TokenStream {
+        let (helper, with) = match self {
+            MogrifyFieldSpecialization::None { .. } => ("raw", leaf.async_with_fn()),
+            MogrifyFieldSpecialization::Option { inner, .. } => {
+                ("opt", inner.async_converter(leaf))
+            }
+            MogrifyFieldSpecialization::Box { inner, .. } => ("box", inner.async_converter(leaf)),
+            MogrifyFieldSpecialization::Sequence { inner, .. } => {
+                ("seq", inner.async_converter(leaf))
+            }
+            MogrifyFieldSpecialization::Map { value, .. } => ("pairs", value.async_converter(leaf)),
+        };
+        let helper = format_ident!("mogrify_{}_async", helper);
+        match self.collection_generics(quote!(_,)) {
+            Some(generics) => {
+                quote!(|value| ::mogrify::util::#helper::<#generics>(scope, #with, value))
+            }
+            None => quote!(|value| ::mogrify::util::#helper(#with, value)),
+        }
+    }
+    // generic args naming the converted elements and collection for the collection helpers, which
+    // can't always infer them. `future` fills the async helpers' extra `Fut` param.
+    fn collection_generics(
+        &self,
+        future: proc_macro2::TokenStream,
+    ) -> Option<proc_macro2::TokenStream> {
+        match self {
+            MogrifyFieldSpecialization::Sequence { ty, inner, .. } => {
+                let into = inner.ty();
+                Some(quote!(_, #into, _, #future #ty, _, _))
+            }
+            MogrifyFieldSpecialization::Map {
+                ty, key_ty, value, ..
+            } => {
+                let into = value.ty();
+                Some(quote!(_, #key_ty, _, _, #into, _, #future #ty, _, _))
+            }
+            _ => None,
+        }
+    }
+    fn reverse(
+        &self,
+        value: proc_macro2::TokenStream,
+        into: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match self {
+            MogrifyFieldSpecialization::None { .. } => quote!(#into(#value)),
+            MogrifyFieldSpecialization::Option { inner, .. } => {
+                let inner = inner.reverse(quote!(value), into);
+                quote!(#value.map(|value| #inner))
+            }
+            MogrifyFieldSpecialization::Box { inner, .. } => {
+                let inner = inner.reverse(quote!((*#value)), into);
+                quote!(::std::boxed::Box::new(#inner))
+            }
+            MogrifyFieldSpecialization::Sequence { inner, .. } => {
+                let inner = inner.reverse(quote!(value), into);
+                quote!(#value.into_iter().map(|value| #inner).collect())
+            }
+            MogrifyFieldSpecialization::Map { value: inner, .. } => {
+                let inner = inner.reverse(quote!(value), into);
+                quote!(#value.into_iter().map(|(key, value)| (::std::convert::Into::into(key), #inner)).collect())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) enum MogrifyCollection {
+    Vec,
+    VecDeque,
+    LinkedList,
+    HashSet,
+    BTreeSet,
+    HashMap,
+    BTreeMap,
+}
+
+const SEQUENCES: [(&str, MogrifyCollection); 5] = [
+    ("Vec", MogrifyCollection::Vec),
+    ("VecDeque", MogrifyCollection::VecDeque),
+    ("LinkedList", MogrifyCollection::LinkedList),
+    ("HashSet", MogrifyCollection::HashSet),
+    ("BTreeSet", MogrifyCollection::BTreeSet),
+];
+const MAPS: [(&str, MogrifyCollection); 2] = [
+    ("HashMap", MogrifyCollection::HashMap),
+    ("BTreeMap", MogrifyCollection::BTreeMap),
+];
+
+impl MogrifyCollection {
+    // what set elements or map keys need to implement to be collected
+    pub(crate) fn key_bound(self) -> Option<proc_macro2::TokenStream> {
+        match self {
+            MogrifyCollection::HashSet | MogrifyCollection::HashMap => {
+                Some(quote!(::std::hash::Hash + ::std::cmp::Eq))
+            }
+            MogrifyCollection::BTreeSet | MogrifyCollection::BTreeMap => {
+                Some(quote!(::std::cmp::Ord))
+            }
+            MogrifyCollection::Vec
+            | MogrifyCollection::VecDeque
+            | MogrifyCollection::LinkedList => None,
+        }
+    }
+}
 impl MogrifyFieldInfo {
     pub(crate) fn destructure_expr(&self) -> proc_macro2::TokenStream {
         let Self {
@@ -42,23 +363,65 @@ impl MogrifyFieldInfo {
     pub(crate) fn assignment_expr(&self) -> proc_macro2::TokenStream {
         let Self {
             local_ident,
-            source_ident,
+            target_ident,
             ..
         } = self;
-        match source_ident {
+        match target_ident {
             None => quote!(#local_ident.unwrap()),
-            Some(source_ident) => quote!(#source_ident: #local_ident.unwrap()),
+            Some(target_ident) => quote!(#target_ident: #local_ident.unwrap()),
         }
     }
-    pub(crate) fn conversion(&self, field_count: usize) -> proc_macro2::TokenStream {
+    pub(crate) fn reverse_destructure_expr(&self) -> proc_macro2::TokenStream {
+        let Self {
+            local_ident,
+            target_ident,
+            ..
+        } = self;
+        match target_ident {
+            None => quote!(#local_ident),
+            Some(target_ident) => quote!(#target_ident: #local_ident),
+        }
+    }
+    pub(crate) fn reverse_assignment_expr(&self) -> Result<proc_macro2::TokenStream, syn::Error> {
+        // the source would be `Option<Option<T>>`, which can't tell a defaulted value from a given one
+        if self.attrs.require && self.attrs.default.is_some() {
+            return Err(syn::Error::new_spanned(
+                &self.ty,
+                r#"reverse does not support "require" together with "default""#,
+            ));
+        }
+        let conversion = self.reverse_conversion();
+        Ok(match &self.source_ident {
+            None => conversion,
+            Some(source_ident) => quote!(#source_ident: #conversion),
+        })
+    }
+    pub(crate) fn reverse_conversion(&self) -> proc_macro2::TokenStream {
         let Self {
-            idx,
             local_ident,
-            source_ident,
             attrs,
             specialization,
+            ..
         } = self;
-        let mut conversion_expr = match &attrs.default {
+        let into = match &attrs.enumeration {
+            Some(enumeration) => {
+                quote!((|value| ::std::convert::Into::<i32>::into(::std::convert::Into::<#enumeration>::into(value))))
+            }
+            None => quote!(::std::convert::Into::into),
+        };
+        let mut conversion_expr = specialization.reverse(quote!(#local_ident), &into);
+        // `require` and `default` both unwrap an `Option` on the way in, so re-wrap it on the way out
+        if attrs.require || attrs.default.is_some() {
+            conversion_expr = quote!(::std::option::Option::Some(#conversion_expr));
+        }
+        conversion_expr
+    }
+    // `default` and `require` handling, before any conversion
+    fn unwrap_expr(&self) -> proc_macro2::TokenStream {
+        let Self {
+            local_ident, attrs, ..
+        } = self;
+        let mut unwrap_expr = match &attrs.default {
             None => quote!(Ok(#local_ident)),
             Some(None) => {
                 quote!(Ok(#local_ident.unwrap_or_default()))
@@ -68,69 +431,116 @@ impl MogrifyFieldInfo {
             }
         };
         if attrs.require {
-            conversion_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_require)));
-        }
-        if attrs.raw {
-            match &attrs.parse {
-                None => conversion_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_raw))),
-                Some(parse) => conversion_expr.append_all(
-                    quote!(.and_then(|value| ::mogrify::util::mogrify_raw_with(#parse, value))),
-                ),
-            }
-        } else {
-            match specialization {
-                MogrifyFieldSpecialization::None { .. } => match &attrs.parse {
-                    None => {
-                        conversion_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_raw)));
-                    }
-                    Some(parse) => {
-                        conversion_expr.append_all(quote!(.and_then(|value| ::mogrify::util::mogrify_raw_with(#parse, value))));
-                    }
-                },
-                MogrifyFieldSpecialization::Option { .. } => match &attrs.parse {
-                    None => {
-                        conversion_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_opt)));
-                    }
-                    Some(parse) => {
-                        conversion_expr.append_all(quote!(.and_then(|value| ::mogrify::util::mogrify_opt_with(#parse, value))));
-                    }
-                },
-                MogrifyFieldSpecialization::Vec { .. } => match &attrs.parse {
-                    None => {
-                        conversion_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_vec)));
-                    }
-                    Some(parse) => {
-                        conversion_expr.append_all(quote!(.and_then(|value| ::mogrify::util::mogrify_vec_with(#parse, value))));
-                    }
-                },
-                MogrifyFieldSpecialization::Map { .. } => match &attrs.parse {
-                    None => {
-                        conversion_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_map)));
-                    }
-                    Some(parse) => {
-                        conversion_expr.append_all(quote!(.and_then(|value| ::mogrify::util::mogrify_map_with(#parse, value))));
-                    }
-                },
-            }
-        }
-        if let Some(and_then) = &attrs.and_then {
-            conversion_expr.append_all(quote!(.and_then(|r| #and_then(r).map_err(::mogrify::MogrificationError::wrapping))))
+            unwrap_expr.append_all(quote!(.and_then(::mogrify::util::mogrify_require)));
         }
-
+        unwrap_expr
+    }
+    fn path_expr(&self, field_count: usize) -> proc_macro2::TokenStream {
+        let Self {
+            idx, source_ident, ..
+        } = self;
         match (source_ident, field_count) {
             (Some(source_ident), _) => {
                 let field = source_ident.to_string();
-                conversion_expr.append_all(quote!(.at_field(#field)));
+                quote!(.at_field(#field))
             }
             (None, 1) => {
                 // skip tracking the index if there's only a single item in the tuple
+                quote!()
             }
             (None, _) => {
-                conversion_expr.append_all(quote!(.at_index(#idx)));
+                quote!(.at_index(#idx))
+            }
+        }
+    }
+    pub(crate) fn conversion(
+        &self,
+        field_count: usize,
+        source: &MogrifyStructAttrs,
+    ) -> proc_macro2::TokenStream {
+        let contextual = source.context.is_some();
+        let Self {
+            attrs,
+            specialization,
+            ..
+        } = self;
+        let mut conversion_expr = self.unwrap_expr();
+        // with a context, `parse` and `and_then` may take `&Ctx` as a second argument
+        let call = |with: proc_macro2::TokenStream| {
+            if contextual {
+                quote!(|value| ::mogrify::util::with_context(&#with, value, ctx))
+            } else {
+                with
+            }
+        };
+        let parse = match &attrs.enumeration {
+            Some(enumeration) => {
+                Some(quote!(::mogrify::util::mogrify_enumeration::<#enumeration, _, _>))
+            }
+            None if attrs.context => Some(call(quote!(::mogrify::TryFromWith::try_from_with))),
+            None => attrs.parse.as_ref().map(|parse| call(quote!(#parse))),
+        };
+        let leaf = MogrifyLeaf {
+            parse,
+            checks: attrs.validators.iter().map(|v| v.check()).collect(),
+            and_then: attrs
+                .and_then
+                .as_ref()
+                .map(|and_then| call(quote!(#and_then))),
+        };
+        let converter = specialization.converter(&leaf);
+        conversion_expr.append_all(quote!(.and_then(#converter)));
+        for validator in &attrs.whole {
+            let check = validator.check();
+            conversion_expr.append_all(quote!(.and_then(#check)));
+        }
+        let path_expr = self.path_expr(field_count);
+        if path_expr.is_empty() {
+            return conversion_expr;
+        }
+        if !source.warnings {
+            return quote!(#conversion_expr #path_expr);
+        }
+        // warnings raised while converting the field get the same path as its failures
+        quote! {{
+            let mark = warnings.mark();
+            let result = #conversion_expr #path_expr;
+            mark #path_expr;
+            result
+        }}
+    }
+    // a future resolving to the same result as `conversion`, with `parse` and `and_then` awaited
+    pub(crate) fn async_conversion(&self, field_count: usize) -> proc_macro2::TokenStream {
+        let Self {
+            attrs,
+            specialization,
+            ..
+        } = self;
+        let unwrap_expr = self.unwrap_expr();
+        let parse = match &attrs.enumeration {
+            Some(enumeration) => Some(
+                quote!(|value| ::std::future::ready(::mogrify::util::mogrify_enumeration::<#enumeration, _, _>(value))),
+            ),
+            None => attrs.parse.as_ref().map(|parse| quote!(#parse)),
+        };
+        let leaf = MogrifyLeaf {
+            parse,
+            checks: attrs.validators.iter().map(|v| v.check()).collect(),
+            and_then: attrs.and_then.as_ref().map(|and_then| quote!(#and_then)),
+        };
+        let converter = specialization.async_converter(&leaf);
+        let whole = attrs.whole.iter().map(|v| v.check());
+        let path_expr = self.path_expr(field_count);
+        quote! {
+            async move {
+                (match #unwrap_expr {
+                    Ok(value) => (#converter)(value).await,
+                    Err(err) => Err(err),
+                })
+                #(.and_then(#whole))*
+                #path_expr
             }
         }
-
-        conversion_expr
     }
 }
 
@@ -140,38 +550,40 @@ fn type_shape_check(path: &TypePath, name: &'static str, generic_count: usize) -
         && last.ident == name
         && matches!(&last.arguments, PathArguments::AngleBracketed(args) if args.args.len() == generic_count)
 }
-fn extract_single_generic(path: TypePath) -> GenericArgument {
-    match path
-        .path
-        .segments
-        .into_iter()
-        .last()
-        .expect("expectation failed")
-        .arguments
-    {
-        PathArguments::AngleBracketed(inner) => {
-            inner.args.into_iter().next().expect("expectation failed")
-        }
-        _ => panic!("expectation failed"),
+// Unsized types that are commonly boxed, next to slices and trait objects
+const UNSIZED: [&str; 4] = ["str", "Path", "OsStr", "CStr"];
+
+fn is_sized_path(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|last| !UNSIZED.iter().any(|name| last.ident == name)),
+        _ => false,
     }
 }
-fn extract_double_generic(path: TypePath) -> (GenericArgument, GenericArgument) {
-    match path
-        .path
-        .segments
-        .into_iter()
-        .last()
-        .expect("expectation failed")
-        .arguments
-    {
-        PathArguments::AngleBracketed(inner) => {
-            let mut iter = inner.args.into_iter();
-            (
-                iter.next().expect("expectation failed"),
-                iter.next().expect("expectation failed"),
-            )
-        }
-        _ => panic!("expectation failed"),
+fn generic_args(path: &TypePath) -> Result<impl Iterator<Item = GenericArgument>, syn::Error> {
+    match path.path.segments.last().map(|last| &last.arguments) {
+        Some(PathArguments::AngleBracketed(inner)) => Ok(inner.args.clone().into_iter()),
+        _ => Err(syn::Error::new_spanned(path, "expected generic arguments")),
+    }
+}
+fn extract_single_generic(path: &TypePath) -> Result<GenericArgument, syn::Error> {
+    generic_args(path)?
+        .next()
+        .ok_or_else(|| syn::Error::new_spanned(path, "expected one generic argument"))
+}
+fn extract_double_generic(
+    path: &TypePath,
+) -> Result<(GenericArgument, GenericArgument), syn::Error> {
+    let mut args = generic_args(path)?;
+    match (args.next(), args.next()) {
+        (Some(first), Some(second)) => Ok((first, second)),
+        _ => Err(syn::Error::new_spanned(
+            path,
+            "expected two generic arguments",
+        )),
     }
 }
 
@@ -180,26 +592,21 @@ impl TryFrom<(usize, Field)> for MogrifyFieldInfo {
 
     fn try_from((idx, value): (usize, Field)) -> Result<Self, Self::Error> {
         let attrs: MogrifyFieldAttrs = extract_mogrify_meta(value.attrs).try_into()?;
-        let specialization = match value.ty {
-            Type::Path(path) if type_shape_check(&path, "Option", 1) => {
-                let ty = extract_single_generic(path);
-                MogrifyFieldSpecialization::Option { ty }
-            }
-            Type::Path(path) if type_shape_check(&path, "Vec", 1) => {
-                let ty = extract_single_generic(path);
-                MogrifyFieldSpecialization::Vec { ty }
-            }
-            Type::Path(path) if type_shape_check(&path, "HashMap", 2) => {
-                let (key_ty, value_ty) = extract_double_generic(path);
-                MogrifyFieldSpecialization::Map { key_ty, value_ty }
-            }
-            _ => MogrifyFieldSpecialization::None { ty: value.ty },
+        let ty = value.ty.clone();
+        let specialization = if attrs.raw {
+            // Disable Option/Vec/HashMap specialization
+            MogrifyFieldSpecialization::None { ty: value.ty }
+        } else {
+            MogrifyFieldSpecialization::from_type(value.ty)?
         };
         let local_ident = Ident::new(&format!("local{idx}"), Span::mixed_site());
+        let source_ident = attrs.from.clone().or_else(|| value.ident.clone());
         Ok(MogrifyFieldInfo {
             idx,
             local_ident,
-            source_ident: value.ident,
+            source_ident,
+            target_ident: value.ident,
+            ty,
             attrs,
             specialization,
         })
@@ -0,0 +1,226 @@
+use crate::attrs::MogrifyStructAttrs;
+use crate::fields::{MogrifyFieldInfo, MogrifyFieldSpecialization};
+use proc_macro2::{Ident, TokenStream};
+use quote::ToTokens;
+use std::collections::HashMap;
+use syn::punctuated::Punctuated;
+use syn::visit_mut::VisitMut;
+use syn::{
+    parse_quote, GenericArgument, GenericParam, Generics, PathArguments, Type, WherePredicate,
+};
+
+/// Pairs the target's type params with the source's generic arguments by position, so that
+/// a target field type can be rewritten into the matching source field type.
+pub(crate) struct ParamSubstitution {
+    params: HashMap<Ident, Type>,
+    // async conversions hold both sides across awaits, in a `Send` future
+    asynchronous: bool,
+}
+
+impl VisitMut for ParamSubstitution {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            if let Some(ident) = path.path.get_ident() {
+                if path.qself.is_none() {
+                    if let Some(replacement) = self.params.get(ident) {
+                        *ty = replacement.clone();
+                        return;
+                    }
+                }
+            }
+        }
+        syn::visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+impl ParamSubstitution {
+    pub(crate) fn new(target: &Generics, source: &MogrifyStructAttrs) -> ParamSubstitution {
+        let last = source
+            .source
+            .path
+            .segments
+            .last()
+            .expect("paths can't be empty");
+        let args = match &last.arguments {
+            PathArguments::AngleBracketed(args) => args
+                .args
+                .iter()
+                .filter_map(|arg| match arg {
+                    GenericArgument::Type(ty) => Some(ty.clone()),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        let params = target
+            .type_params()
+            .zip(args)
+            // a param shared between source and target converts into itself, no bound required
+            .filter(
+                |(param, ty)| !matches!(ty, Type::Path(path) if path.path.is_ident(&param.ident)),
+            )
+            .map(|(param, ty)| (param.ident.clone(), ty))
+            .collect();
+        ParamSubstitution {
+            params,
+            asynchronous: source.asynchronous,
+        }
+    }
+
+    /// The source side of a target type, or `None` if it doesn't mention any substituted param
+    fn substitute(&mut self, ty: &Type) -> Option<Type> {
+        let mut substituted = ty.clone();
+        self.visit_type_mut(&mut substituted);
+        if substituted.to_token_stream().to_string() == ty.to_token_stream().to_string() {
+            None
+        } else {
+            Some(substituted)
+        }
+    }
+
+    pub(crate) fn try_from_bounds(&mut self, fields: &[MogrifyFieldInfo]) -> Vec<WherePredicate> {
+        let mut bounds = Vec::new();
+        for field in fields {
+            if self.asynchronous {
+                let types = ConvertedTypes::of(field);
+                for leaf in types.keys.into_iter().chain(types.values) {
+                    if let Some(source) = self.substitute(leaf) {
+                        bounds.push(parse_quote!(#leaf: ::std::marker::Send));
+                        bounds.push(parse_quote!(#source: ::std::marker::Send));
+                    }
+                }
+            }
+            if let (Some(_), Some(source)) = (&field.attrs.default, self.substitute(&field.ty)) {
+                bounds.push(parse_quote!(#source: ::std::default::Default));
+            }
+            if field.attrs.parse.is_some()
+                || field.attrs.enumeration.is_some()
+                || field.attrs.context
+            {
+                // custom parse functions carry their own bounds
+                continue;
+            }
+            let types = ConvertedTypes::of(field);
+            for (keyed, bound) in types.keyed {
+                if self.substitute(keyed).is_some() {
+                    bounds.push(parse_quote!(#keyed: #bound));
+                }
+            }
+            for key in types.keys {
+                if let Some(source) = self.substitute(key) {
+                    bounds.push(parse_quote!(#source: ::std::string::ToString));
+                    bounds.extend(try_from_bound(key, &source));
+                }
+            }
+            for value in types.values {
+                if let Some(source) = self.substitute(value) {
+                    bounds.extend(try_from_bound(value, &source));
+                }
+            }
+        }
+        bounds
+    }
+
+    pub(crate) fn reverse_bounds(&mut self, fields: &[MogrifyFieldInfo]) -> Vec<WherePredicate> {
+        let mut bounds = Vec::new();
+        for field in fields {
+            if field.attrs.enumeration.is_some() {
+                continue;
+            }
+            let types = ConvertedTypes::of(field);
+            for (keyed, bound) in types.keyed {
+                if let Some(source) = self.substitute(keyed) {
+                    bounds.push(parse_quote!(#source: #bound));
+                }
+            }
+            for key in types.keys {
+                if let Some(source) = self.substitute(key) {
+                    bounds.push(parse_quote!(#key: ::std::convert::Into<#source>));
+                }
+            }
+            for value in types.values {
+                if let Some(source) = self.substitute(value) {
+                    bounds.push(parse_quote!(#value: ::std::convert::Into<#source>));
+                }
+            }
+        }
+        bounds
+    }
+}
+
+fn try_from_bound(target: &Type, source: &Type) -> [WherePredicate; 2] {
+    [
+        parse_quote!(#target: ::std::convert::TryFrom<#source>),
+        parse_quote!(<#target as ::std::convert::TryFrom<#source>>::Error: ::std::error::Error + ::std::marker::Send + ::std::marker::Sync + 'static),
+    ]
+}
+
+/// The types a field converts element by element, collected from every level of its specialization
+#[derive(Default)]
+struct ConvertedTypes<'a> {
+    keys: Vec<&'a Type>,
+    values: Vec<&'a Type>,
+    // set elements and map keys, with what they need to implement to be collected
+    keyed: Vec<(&'a Type, TokenStream)>,
+}
+
+impl<'a> ConvertedTypes<'a> {
+    fn of(field: &'a MogrifyFieldInfo) -> ConvertedTypes<'a> {
+        let mut types = ConvertedTypes::default();
+        types.walk(&field.specialization);
+        types
+    }
+
+    fn walk(&mut self, specialization: &'a MogrifyFieldSpecialization) {
+        match specialization {
+            MogrifyFieldSpecialization::None { ty } => self.values.push(ty),
+            MogrifyFieldSpecialization::Option { inner, .. }
+            | MogrifyFieldSpecialization::Box { inner, .. } => self.walk(inner),
+            MogrifyFieldSpecialization::Sequence {
+                collection, inner, ..
+            } => {
+                if let Some(bound) = collection.key_bound() {
+                    self.keyed.push((inner.ty(), bound));
+                }
+                self.walk(inner);
+            }
+            MogrifyFieldSpecialization::Map {
+                collection,
+                key_ty,
+                value,
+                ..
+            } => {
+                if let Some(bound) = collection.key_bound() {
+                    self.keyed.push((key_ty, bound));
+                }
+                self.keys.push(key_ty);
+                self.walk(value);
+            }
+        }
+    }
+}
+
+/// Combines the target's generics with any source-only params and the where predicates for this impl
+pub(crate) fn merge_generics(
+    target: &Generics,
+    source: &MogrifyStructAttrs,
+    inferred: Vec<WherePredicate>,
+) -> Generics {
+    let mut generics = target.clone();
+    // lifetimes have to be declared before any other params
+    let (lifetimes, others): (Vec<GenericParam>, Vec<GenericParam>) = target
+        .params
+        .iter()
+        .chain(source.generics.params.iter())
+        .cloned()
+        .partition(|param| matches!(param, GenericParam::Lifetime(_)));
+    generics.params = lifetimes
+        .into_iter()
+        .chain(others)
+        .collect::<Punctuated<_, _>>();
+    generics
+        .make_where_clause()
+        .predicates
+        .extend(source.bound.clone().unwrap_or(inferred));
+    generics
+}
@@ -1,6 +1,7 @@
 mod attrs;
 mod derive;
 mod fields;
+mod generics;
 
 use crate::derive::derive_inner;
 use proc_macro::TokenStream;
@@ -0,0 +1,14 @@
+use crate::MogrificationError;
+use std::future::Future;
+
+/// The async counterpart to `TryFrom`, for conversions whose `parse` or `and_then` functions
+/// need to await I/O. Derived with `#[mogrify(Source, async)]`.
+///
+/// Fields still convert through `TryFrom` unless they have a `parse`, so a field whose type is
+/// itself async-derived, like `Inner`, needs `#[mogrify(parse = Inner::mogrify_from)]`.
+pub trait AsyncMogrify<Source>: Sized {
+    /// `Send`, so conversions can run on multi-threaded executors
+    fn mogrify_from(
+        source: Source,
+    ) -> impl Future<Output = Result<Self, MogrificationError>> + Send;
+}
@@ -0,0 +1,7 @@
+use crate::MogrificationError;
+
+/// `TryFrom` with access to request-scoped data, like tenant config or the current time.
+/// Derived with `#[mogrify(Source, context = Ctx)]`.
+pub trait TryFromWith<Source, Ctx>: Sized {
+    fn try_from_with(source: Source, ctx: &Ctx) -> Result<Self, MogrificationError>;
+}
@@ -1,4 +1,5 @@
-use crate::failure::MogrifyFailure;
+use crate::failure::{FailureCode, MogrifyFailure};
+use crate::message::{English, MessageCatalog, MessageParams};
 use crate::path::PathTracker;
 use std::any::Any;
 use std::error::Error;
@@ -7,80 +8,226 @@ use std::fmt::{Display, Formatter};
 #[derive(Debug)]
 pub struct MogrificationError {
     pub(crate) failures: Vec<MogrifyFailure>,
+    // failures past the cap, only counted
+    pub(crate) dropped: usize,
+    // how many failures `collect` keeps
+    pub(crate) max_failures: usize,
 }
 
 impl MogrificationError {
+    /// An empty error to [`collect`](MogrificationError::collect) into, keeping at most
+    /// `max_failures` failures. Failures past the cap are only counted, see
+    /// [`MogrificationError::dropped`].
+    ///
+    /// The cap is honoured by `collect` and `extend` on the capped error,
+    /// [`MogrificationError::condense_capped`], the `util::mogrify_seq_in` and
+    /// `util::mogrify_pairs_in` helpers through their `Scope`, and conversions derived with
+    /// `max_failures = N`. Everything else, like `condense` and `util::mogrify_vec_with`, keeps
+    /// every failure.
+    pub fn capped(max_failures: usize) -> MogrificationError {
+        MogrificationError {
+            max_failures,
+            ..MogrificationError::empty()
+        }
+    }
+    /// The cap `collect` honours, unlimited unless built with [`MogrificationError::capped`]
+    pub fn max_failures(&self) -> usize {
+        self.max_failures
+    }
     pub fn new<S: Into<String>>(msg: S) -> MogrificationError {
+        MogrificationError::new_coded(FailureCode::Invalid, msg)
+    }
+    pub fn new_with<S: Into<String>, Err: Error + Send + Sync + 'static>(
+        msg: S,
+        err: Err,
+    ) -> MogrificationError {
+        MogrificationError::new_coded_with(FailureCode::Invalid, msg, err)
+    }
+    pub fn new_coded<S: Into<String>>(code: FailureCode, msg: S) -> MogrificationError {
         MogrificationError {
             failures: vec![MogrifyFailure {
                 path: PathTracker::new(),
+                code,
                 message: msg.into(),
+                key: None,
+                params: MessageParams::new(),
                 underlying: None,
             }],
+            ..MogrificationError::empty()
         }
     }
-    pub fn new_with<S: Into<String>, Err: Error + Send + Sync + 'static>(
+    pub fn new_coded_with<S: Into<String>, Err: Error + Send + Sync + 'static>(
+        code: FailureCode,
         msg: S,
         err: Err,
     ) -> MogrificationError {
         MogrificationError {
             failures: vec![MogrifyFailure {
                 path: PathTracker::new(),
+                code,
                 message: msg.into(),
+                key: None,
+                params: MessageParams::new(),
                 underlying: Some(Box::new(err)),
             }],
+            ..MogrificationError::empty()
         }
     }
+    /// A built in failure, with its message rendered by the `English` catalog
+    pub(crate) fn new_keyed(
+        code: FailureCode,
+        key: &'static str,
+        params: MessageParams,
+    ) -> MogrificationError {
+        let message = English
+            .render(key, &params)
+            .unwrap_or_else(|| key.to_string());
+        MogrificationError::new_coded(code, message).keyed(key, params)
+    }
+    pub(crate) fn with_underlying<Err: Error + Send + Sync + 'static>(
+        mut self,
+        err: Err,
+    ) -> MogrificationError {
+        self.failures[0].underlying = Some(Box::new(err));
+        self
+    }
+    /// Attaches a `MessageCatalog` key and params to every failure that isn't keyed yet, keeping
+    /// their messages as the English fallback
+    pub fn keyed(mut self, key: &'static str, params: MessageParams) -> MogrificationError {
+        for failure in self.failures.iter_mut().filter(|f| f.key.is_none()) {
+            failure.key = Some(key);
+            failure.params = params.clone();
+        }
+        self
+    }
     pub fn wrapping<T: Any + Error + Send + Sync + 'static>(
         mut underlying: T,
     ) -> MogrificationError {
         let as_maybe_mogrify = &mut underlying as &mut dyn Any;
         match as_maybe_mogrify.downcast_mut::<MogrificationError>() {
-            None => MogrificationError {
-                failures: vec![MogrifyFailure {
-                    path: PathTracker::new(),
-                    message: "mogrify failure".to_string(),
-                    underlying: Some(Box::new(underlying)),
-                }],
-            },
+            None => {
+                let params = MessageParams::new().with("error", &underlying);
+                MogrificationError::new_keyed(FailureCode::Invalid, "invalid", params)
+                    .with_underlying(underlying)
+            }
             Some(underlying) => {
-                let mut known = MogrificationError { failures: vec![] };
+                let mut known = MogrificationError::empty();
                 std::mem::swap(&mut known.failures, &mut underlying.failures);
+                std::mem::swap(&mut known.dropped, &mut underlying.dropped);
                 known
             }
         }
     }
+    pub(crate) fn empty() -> MogrificationError {
+        MogrificationError {
+            failures: vec![],
+            dropped: 0,
+            max_failures: usize::MAX,
+        }
+    }
+    pub(crate) fn is_empty(&self) -> bool {
+        self.failures.is_empty() && self.dropped == 0
+    }
     pub fn failures(&self) -> &Vec<MogrifyFailure> {
         &self.failures
     }
+    /// How many failures were left out of [`MogrificationError::failures`] by the cap
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+    pub fn failures_with_code<'a>(
+        &'a self,
+        code: &'a FailureCode,
+    ) -> impl Iterator<Item = &'a MogrifyFailure> + 'a {
+        self.failures.iter().filter(move |f| &f.code == code)
+    }
+    /// Failures at `prefix` or anywhere beneath it
+    pub fn failures_under<'a>(
+        &'a self,
+        prefix: &'a PathTracker,
+    ) -> impl Iterator<Item = &'a MogrifyFailure> + 'a {
+        self.failures
+            .iter()
+            .filter(move |f| f.path.starts_with(prefix))
+    }
+    pub fn has_code(&self, code: &FailureCode) -> bool {
+        self.failures.iter().any(|f| &f.code == code)
+    }
+    /// Replaces the code on every failure
+    pub fn recode(mut self, code: FailureCode) -> MogrificationError {
+        for failure in self.failures.iter_mut() {
+            failure.code = code.clone();
+        }
+        self
+    }
+    /// The first line of `Display`: the only failure, or how many there were
+    pub fn summary(&self) -> String {
+        match self.failures.as_slice() {
+            [only] if self.dropped == 0 => only.to_string(),
+            failures => format!("found {} mogrify failures", failures.len() + self.dropped),
+        }
+    }
     pub fn into_box(self) -> Box<dyn Error + Send + Sync + 'static> {
         Box::new(self)
     }
     pub fn condense(errors: Vec<MogrificationError>) -> Result<(), MogrificationError> {
+        MogrificationError::condense_capped(errors, usize::MAX)
+    }
+    /// Like [`MogrificationError::condense`], keeping at most `max_failures` failures
+    pub fn condense_capped(
+        errors: Vec<MogrificationError>,
+        max_failures: usize,
+    ) -> Result<(), MogrificationError> {
         if errors.is_empty() {
             Ok(())
         } else {
-            Err(MogrificationError {
-                failures: errors.into_iter().flat_map(|e| e.failures).collect(),
-            })
+            let mut condensed = MogrificationError::capped(max_failures);
+            condensed.extend(errors);
+            Err(condensed)
+        }
+    }
+    /// `Err(self)` if anything was collected
+    pub fn into_result(self) -> Result<(), MogrificationError> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
         }
     }
     // todo: remove once we remove the last usage of ValidationError
     pub fn collect(&mut self, mut other: MogrificationError) {
+        let room = self.max_failures.saturating_sub(self.failures.len());
+        if other.failures.len() > room {
+            other.dropped += other.failures.len() - room;
+            other.failures.truncate(room);
+        }
         self.failures.append(&mut other.failures);
+        self.dropped += other.dropped;
+    }
+}
+
+/// Lets the collection helpers gather failures into a single capped error as they go
+impl Extend<MogrificationError> for MogrificationError {
+    fn extend<T: IntoIterator<Item = MogrificationError>>(&mut self, iter: T) {
+        for err in iter {
+            self.collect(err);
+        }
     }
 }
 impl Display for MogrificationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        if self.failures.len() == 1 {
+        if self.failures.len() == 1 && self.dropped == 0 {
             let err = self.failures.first().unwrap();
             std::fmt::Display::fmt(&err, f)
         } else {
             // todo: test for formatting
-            writeln!(f, "found {} mogrify failures", self.failures.len())?;
+            writeln!(f, "{}", self.summary())?;
             for details in &self.failures {
                 writeln!(f, "    -> {details}")?
             }
+            if self.dropped > 0 {
+                writeln!(f, "    ... and {} more", self.dropped)?
+            }
             Ok(())
         }
     }
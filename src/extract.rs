@@ -0,0 +1,141 @@
+//! An axum extractor that deserializes a raw request type and mogrifies it in one step
+
+use crate::path::{PathPart, PathTracker};
+use crate::util::mogrify_raw;
+use crate::{FailureCode, MessageParams, MogrificationError};
+use axum::extract::{FromRequest, FromRequestParts, Query, RawForm, Request};
+use axum::http::request::Parts;
+use axum::response::{IntoResponse, Response};
+use axum::{Form, Json};
+use serde::de::DeserializeOwned;
+use serde_path_to_error::Segment;
+use std::any::Any;
+use std::error::Error;
+use std::marker::PhantomData;
+
+/// Extracts `E`'s raw type, like `RawFoo` from `Json<RawFoo>`, then converts it into `T`.
+/// Supports `Json`, `Query` and `Form`. Deserialization failures and conversion failures are
+/// both rejected with a 422 problem document, at the path they happened. Malformed bodies and
+/// missing content types are rejected by axum as usual.
+///
+/// Handlers destructure it like `Parsed { value, .. }: Parsed<Json<RawFoo>, Foo>`.
+pub struct Parsed<E, T> {
+    pub value: T,
+    extractor: PhantomData<fn() -> E>,
+}
+
+impl<E, T> Parsed<E, T> {
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    // deserialization failures first, then conversion failures
+    fn mogrify<R, DeErr, Err>(
+        raw: Result<R, serde_path_to_error::Error<DeErr>>,
+    ) -> Result<Self, MogrificationError>
+    where
+        T: TryFrom<R, Error = Err>,
+        DeErr: Error + Send + Sync + 'static,
+        Err: Any + Error + Send + Sync + 'static,
+    {
+        let value = mogrify_raw(raw.map_err(deserialize_error)?)?;
+        Ok(Parsed {
+            value,
+            extractor: PhantomData,
+        })
+    }
+}
+
+// Reports a serde failure at the field it happened in, like any other failure
+fn deserialize_error<Err>(err: serde_path_to_error::Error<Err>) -> MogrificationError
+where
+    Err: Error + Send + Sync + 'static,
+{
+    let mut segments = err
+        .path()
+        .iter()
+        .filter_map(|segment| match segment {
+            Segment::Seq { index } => Some(PathPart::Index(*index)),
+            Segment::Map { key } => Some(PathPart::Field(key.clone())),
+            Segment::Enum { variant } => Some(PathPart::Variant(variant.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let underlying = err.into_inner();
+    // serde reports a missing field at the struct missing it, so point at the field itself
+    if let Some(field) = missing_field(&underlying.to_string()) {
+        segments.push(PathPart::Field(field.to_string()));
+    }
+    let path = segments.into_iter().collect::<PathTracker>();
+    let params = MessageParams::new().with("error", &underlying);
+    let mut err = MogrificationError::new_keyed(FailureCode::Parse, "parse", params)
+        .with_underlying(underlying);
+    err.failures[0].path = path;
+    err
+}
+
+// The field named by serde's "missing field `name`" message
+fn missing_field(message: &str) -> Option<&str> {
+    let (field, _) = message.strip_prefix("missing field `")?.split_once('`')?;
+    Some(field)
+}
+
+fn deserialize_urlencoded<R: DeserializeOwned>(
+    input: &[u8],
+) -> Result<R, serde_path_to_error::Error<serde_urlencoded::de::Error>> {
+    let deserializer = serde_urlencoded::Deserializer::new(form_urlencoded::parse(input));
+    serde_path_to_error::deserialize(deserializer)
+}
+
+impl<S, R, T, Err> FromRequest<S> for Parsed<Json<R>, T>
+where
+    S: Send + Sync,
+    R: DeserializeOwned,
+    T: TryFrom<R, Error = Err>,
+    Err: Any + Error + Send + Sync + 'static,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        // parsed into a `Value` first, so axum keeps rejecting invalid JSON with a 400
+        let Json(value) = Json::<serde_json::Value>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        Parsed::mogrify(serde_path_to_error::deserialize(value))
+            .map_err(IntoResponse::into_response)
+    }
+}
+
+impl<S, R, T, Err> FromRequest<S> for Parsed<Form<R>, T>
+where
+    S: Send + Sync,
+    R: DeserializeOwned,
+    T: TryFrom<R, Error = Err>,
+    Err: Any + Error + Send + Sync + 'static,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        // the query string for GET and HEAD, the body otherwise
+        let RawForm(bytes) = RawForm::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        Parsed::mogrify(deserialize_urlencoded(&bytes)).map_err(IntoResponse::into_response)
+    }
+}
+
+impl<S, R, T, Err> FromRequestParts<S> for Parsed<Query<R>, T>
+where
+    S: Send + Sync,
+    R: DeserializeOwned,
+    T: TryFrom<R, Error = Err>,
+    Err: Any + Error + Send + Sync + 'static,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        Parsed::mogrify(deserialize_urlencoded(query.as_bytes()))
+            .map_err(IntoResponse::into_response)
+    }
+}
@@ -1,20 +1,103 @@
-use crate::path::PathTracker;
+use crate::message::{MessageCatalog, MessageParams};
+use crate::path::{PathPart, PathTracker};
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+/// A stable, machine-readable category for a failure, so clients can branch without matching on messages
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FailureCode {
+    /// The default, for `MogrificationError::new` and wrapped `TryFrom` errors
+    Invalid,
+    /// A `require`d value was missing
+    Required,
+    /// A string failed to `parse`
+    Parse,
+    /// Outside a `range(...)`
+    OutOfRange,
+    /// Outside a `len(...)`
+    Length,
+    /// Failed `non_empty`
+    Empty,
+    /// Didn't match a `pattern = "..."`
+    Pattern,
+    /// An `enumeration` value the enum doesn't define
+    UnknownVariant,
+    /// A source variant marked `reject`
+    Rejected,
+    /// A map key failed to convert
+    InvalidKey,
+    Custom(String),
+}
+
+impl FailureCode {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FailureCode::Invalid => "invalid",
+            FailureCode::Required => "required",
+            FailureCode::Parse => "parse",
+            FailureCode::OutOfRange => "out_of_range",
+            FailureCode::Length => "length",
+            FailureCode::Empty => "empty",
+            FailureCode::Pattern => "pattern",
+            FailureCode::UnknownVariant => "unknown_variant",
+            FailureCode::Rejected => "rejected",
+            FailureCode::InvalidKey => "invalid_key",
+            FailureCode::Custom(code) => code.as_str(),
+        }
+    }
+}
+
+impl Display for FailureCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Debug)]
 pub struct MogrifyFailure {
     pub(crate) path: PathTracker,
+    pub(crate) code: FailureCode,
+    // rendered by the `English` catalog for keyed failures
     pub(crate) message: String,
-    pub(crate) underlying: Option<Box<dyn Error + Send + Sync + 'static>>, // todo: Code?  Link?
+    pub(crate) key: Option<&'static str>,
+    pub(crate) params: MessageParams,
+    pub(crate) underlying: Option<Box<dyn Error + Send + Sync + 'static>>, // todo: Link?
 }
 impl MogrifyFailure {
     pub fn path(&self) -> String {
         self.path.to_string()
     }
+    pub fn path_tracker(&self) -> &PathTracker {
+        &self.path
+    }
+    /// The path's segments, root first
+    pub fn segments(&self) -> impl DoubleEndedIterator<Item = &PathPart> + ExactSizeIterator {
+        self.path.segments()
+    }
+    /// The path as an RFC 6901 JSON Pointer, e.g. `/tags/en/3`
+    pub fn json_pointer(&self) -> String {
+        self.path.json_pointer()
+    }
+    pub fn code(&self) -> &FailureCode {
+        &self.code
+    }
     pub fn message(&self) -> &str {
         self.message.as_str()
     }
+    /// Identifies the message for a `MessageCatalog`, if it has one
+    pub fn key(&self) -> Option<&'static str> {
+        self.key
+    }
+    pub fn params(&self) -> &MessageParams {
+        &self.params
+    }
+    /// The message as rendered by `catalog`, or the English one if it can't
+    pub fn message_in(&self, catalog: &dyn MessageCatalog) -> String {
+        self.key
+            .and_then(|key| catalog.render(key, &self.params))
+            .unwrap_or_else(|| self.message.clone())
+    }
 }
 
 impl Display for MogrifyFailure {
@@ -1,8 +1,32 @@
+mod asynchronous;
+mod context;
 mod error;
+#[cfg(feature = "axum")]
+mod extract;
 mod failure;
+mod message;
 mod path;
+#[cfg(feature = "serde")]
+mod problem;
+#[cfg(feature = "serde")]
+mod report;
+#[cfg(feature = "tonic")]
+mod status;
 pub mod util;
+pub mod validate;
+mod warnings;
 
+pub use asynchronous::AsyncMogrify;
+pub use context::TryFromWith;
 pub use error::MogrificationError;
+#[cfg(feature = "axum")]
+pub use extract::Parsed;
+pub use failure::{FailureCode, MogrifyFailure};
+pub use message::{English, MessageCatalog, MessageParams};
 pub use mogrify_derive::Mogrify;
-pub use path::Pathed;
+pub use path::{ParsePathError, PathPart, PathTracker, Pathed};
+#[cfg(feature = "serde")]
+pub use problem::{ProblemDetails, ProblemError};
+#[cfg(feature = "serde")]
+pub use report::{FailureReport, MogrificationReport};
+pub use warnings::{Mogrified, TryFromWarned, Warnings};
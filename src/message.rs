@@ -0,0 +1,83 @@
+//! Failure messages as a key plus named parameters, so they can be rendered in other languages.
+//! Built in failures are keyed, and `Display` renders them with the [`English`] catalog.
+
+/// The named values a keyed message is rendered from, like `min` and `max`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MessageParams {
+    params: Vec<(&'static str, String)>,
+}
+
+impl MessageParams {
+    pub fn new() -> MessageParams {
+        MessageParams::default()
+    }
+
+    pub fn with<V: ToString>(mut self, name: &'static str, value: V) -> MessageParams {
+        self.params.push((name, value.to_string()));
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| *param == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty()
+    }
+}
+
+/// Renders keyed failure messages, e.g. one implementation per locale
+pub trait MessageCatalog {
+    /// `None` for keys the catalog doesn't know, which fall back to the failure's English message
+    fn render(&self, key: &str, params: &MessageParams) -> Option<String>;
+}
+
+/// The default messages. Its keys, and the params each is rendered with:
+///
+/// | key | params |
+/// |---|---|
+/// | `invalid` | `error` |
+/// | `required` | |
+/// | `parse` | `error` |
+/// | `unknown_variant` | `value` |
+/// | `empty` | |
+/// | `length.between`, `length.min`, `length.max` | `min` and/or `max`, `actual`, `unit`, `units` |
+/// | `range.between`, `range.min`, `range.max` | `min` and/or `max`, `actual` |
+/// | `pattern` | `pattern` |
+pub struct English;
+
+impl MessageCatalog for English {
+    fn render(&self, key: &str, params: &MessageParams) -> Option<String> {
+        let param = |name| params.get(name).unwrap_or_default();
+        // `unit` and `units` are the singular and plural of what a length counts
+        let count = |name| match param(name) {
+            "1" => format!("1 {}", param("unit")),
+            n => format!("{n} {}", param("units")),
+        };
+        let message = match key {
+            "invalid" => "mogrify failure".to_string(),
+            "required" => "Value is required".to_string(),
+            "parse" => param("error").to_string(),
+            "unknown_variant" => format!("Unknown enum value {}", param("value")),
+            "empty" => "must not be empty".to_string(),
+            "length.between" => format!("must be between {} and {}", param("min"), count("max")),
+            "length.min" => format!("must be at least {}", count("min")),
+            "length.max" => format!("must be at most {}", count("max")),
+            "range.between" => format!("must be between {} and {}", param("min"), param("max")),
+            "range.min" => format!("must be at least {}", param("min")),
+            "range.max" => format!("must be at most {}", param("max")),
+            "pattern" => format!("must match the pattern {}", param("pattern")),
+            _ => return None,
+        };
+        Some(message)
+    }
+}
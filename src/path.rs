@@ -1,15 +1,45 @@
 use crate::MogrificationError;
+use std::borrow::Cow;
+use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::str::FromStr;
 
-#[derive(Debug)]
-pub(crate) enum PathPart {
+/// A single step of a failure's path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum PathPart {
+    /// `.name`
     Field(String),
+    /// `[3]`
     Index(usize),
+    /// `["key"]`
     Key(String),
+    /// `::Variant`
+    Variant(String),
 }
 
-#[derive(Debug)]
-pub(crate) struct PathTracker {
+impl PathPart {
+    // The segment as a JSON Pointer token, before escaping
+    fn token(&self) -> Cow<'_, str> {
+        match self {
+            PathPart::Field(name) | PathPart::Key(name) | PathPart::Variant(name) => {
+                Cow::Borrowed(name)
+            }
+            PathPart::Index(i) => Cow::Owned(i.to_string()),
+        }
+    }
+}
+
+/// Where a failure happened, rendered like `.tags["en"][3]`.
+/// Names containing `\`, `.`, `[` or `:`, and keys containing `\` or `"`, escape them with `\`.
+/// Parses from either that format or a JSON Pointer like `/tags/en/3`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PathTracker {
     pub(crate) parts: Vec<PathPart>,
 }
 impl PathTracker {
@@ -18,16 +48,205 @@ impl PathTracker {
             parts: Vec::with_capacity(3), // seems reasonable nesting level
         }
     }
+
+    /// The path's segments, root first
+    pub fn segments(&self) -> impl DoubleEndedIterator<Item = &PathPart> + ExactSizeIterator {
+        // stored leaf first, since segments are pushed on as errors move up
+        self.parts.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Whether `prefix` is this path or one of its ancestors. Segments are compared as JSON
+    /// Pointer tokens, so a prefix parsed from a pointer matches keys and variants too.
+    pub fn starts_with(&self, prefix: &PathTracker) -> bool {
+        self.parts.len() >= prefix.parts.len()
+            && self
+                .parts
+                .iter()
+                .rev()
+                .zip(prefix.parts.iter().rev())
+                .all(|(part, prefix)| part.token() == prefix.token())
+    }
+
+    /// The path without its last segment, or `None` for the root
+    pub fn parent(&self) -> Option<PathTracker> {
+        self.parts.split_first().map(|(_, parent)| PathTracker {
+            parts: parent.to_vec(),
+        })
+    }
+
+    /// Renders the path as an RFC 6901 JSON Pointer, e.g. `/tags/en/3`
+    pub fn json_pointer(&self) -> String {
+        let mut pointer = String::new();
+        for part in self.parts.iter().rev() {
+            pointer.push('/');
+            pointer.push_str(&part.token().replace('~', "~0").replace('/', "~1"));
+        }
+        pointer
+    }
+
+    fn from_json_pointer(pointer: &str) -> Result<PathTracker, ParsePathError> {
+        let mut parts = Vec::new();
+        // the leading `/` yields an empty first token
+        for token in pointer.split('/').skip(1) {
+            if let Some(invalid) = token
+                .match_indices('~')
+                .find(|(i, _)| !matches!(token.as_bytes().get(i + 1), Some(b'0' | b'1')))
+            {
+                return Err(ParsePathError::new(
+                    pointer,
+                    format!("invalid escape at byte {} of {token:?}", invalid.0),
+                ));
+            }
+            let token = token.replace("~1", "/").replace("~0", "~");
+            // pointers don't say whether a token is a field, key or variant
+            let is_index = !token.is_empty()
+                && token.bytes().all(|b| b.is_ascii_digit())
+                && (token == "0" || !token.starts_with('0'));
+            match token.parse() {
+                Ok(i) if is_index => parts.push(PathPart::Index(i)),
+                _ => parts.push(PathPart::Field(token)),
+            }
+        }
+        parts.reverse();
+        Ok(PathTracker { parts })
+    }
+
+    fn from_display(path: &str) -> Result<PathTracker, ParsePathError> {
+        let mut parts = Vec::new();
+        let mut rest = path;
+        while !rest.is_empty() {
+            let (part, remaining) = if let Some(rest) = rest.strip_prefix("::") {
+                let (name, remaining) = split_escaped(rest, |c| matches!(c, '.' | '[' | ':'));
+                (PathPart::Variant(name), remaining)
+            } else if let Some(rest) = rest.strip_prefix('.') {
+                let (name, remaining) = split_escaped(rest, |c| matches!(c, '.' | '[' | ':'));
+                (PathPart::Field(name), remaining)
+            } else if let Some(rest) = rest.strip_prefix("[\"") {
+                let (key, remaining) = split_escaped(rest, |c| c == '"');
+                let remaining = remaining
+                    .strip_prefix("\"]")
+                    .ok_or_else(|| ParsePathError::new(path, "unterminated key"))?;
+                (PathPart::Key(key), remaining)
+            } else if let Some(rest) = rest.strip_prefix('[') {
+                let end = rest
+                    .find(']')
+                    .ok_or_else(|| ParsePathError::new(path, "unterminated index"))?;
+                let index = rest[..end].parse().map_err(|_| {
+                    ParsePathError::new(path, format!("invalid index {:?}", &rest[..end]))
+                })?;
+                (PathPart::Index(index), &rest[end + 1..])
+            } else {
+                return Err(ParsePathError::new(
+                    path,
+                    format!("unexpected {rest:?}, expected `.`, `[` or `::`"),
+                ));
+            };
+            if matches!(&part, PathPart::Field(name) | PathPart::Variant(name) if name.is_empty()) {
+                return Err(ParsePathError::new(path, "empty name"));
+            }
+            parts.push(part);
+            rest = remaining;
+        }
+        parts.reverse();
+        Ok(PathTracker { parts })
+    }
+}
+
+// Splits an unescaped name or key off the front of the rest of a rendered path, up to the first
+// unescaped character that ends it
+fn split_escaped(rest: &str, ends: impl Fn(char) -> bool) -> (String, &str) {
+    let mut name = String::new();
+    let mut chars = rest.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => name.extend(chars.next().map(|(_, c)| c)),
+            c if ends(c) => return (name, &rest[i..]),
+            c => name.push(c),
+        }
+    }
+    (name, "")
+}
+
+// Backslash-escapes `\` and any of `special` in a name or key
+fn escape<'a>(name: &'a str, special: &[char]) -> Cow<'a, str> {
+    if !name.contains(|c| c == '\\' || special.contains(&c)) {
+        return Cow::Borrowed(name);
+    }
+    let mut escaped = String::with_capacity(name.len() + 1);
+    for c in name.chars() {
+        if c == '\\' || special.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    Cow::Owned(escaped)
+}
+
+impl Default for PathTracker {
+    fn default() -> Self {
+        PathTracker::new()
+    }
 }
 
+/// Builds a path from its segments, root first
+impl FromIterator<PathPart> for PathTracker {
+    fn from_iter<T: IntoIterator<Item = PathPart>>(iter: T) -> Self {
+        let mut parts = iter.into_iter().collect::<Vec<_>>();
+        parts.reverse();
+        PathTracker { parts }
+    }
+}
+
+impl FromStr for PathTracker {
+    type Err = ParsePathError;
+
+    /// Accepts either the rendered `.tags["en"][3]` format or a JSON Pointer. Pointers can't tell
+    /// fields, keys and variants apart, so their tokens parse as indexes if numeric, fields otherwise.
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        if path.is_empty() || path.starts_with('/') {
+            PathTracker::from_json_pointer(path)
+        } else {
+            PathTracker::from_display(path)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsePathError {
+    path: String,
+    reason: String,
+}
+
+impl ParsePathError {
+    fn new<S: Into<String>>(path: &str, reason: S) -> ParsePathError {
+        ParsePathError {
+            path: path.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl Display for ParsePathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid path {:?}: {}", self.path, self.reason)
+    }
+}
+
+impl Error for ParsePathError {}
+
 impl Display for PathTracker {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         // validation happens at the leaf nodes, then moves up, so later fields are actually earlier paths (hence the .rev())
         for part in self.parts.iter().rev() {
             match &part {
-                PathPart::Field(field) => write!(f, ".{field}")?,
+                PathPart::Field(field) => write!(f, ".{}", escape(field, &['.', '[', ':']))?,
                 PathPart::Index(i) => write!(f, "[{i}]")?,
-                PathPart::Key(k) => write!(f, "[\"{k}\"]")?,
+                PathPart::Key(k) => write!(f, "[\"{}\"]", escape(k, &['"']))?,
+                PathPart::Variant(variant) => write!(f, "::{}", escape(variant, &['.', '[', ':']))?,
             }
         }
         Ok(())
@@ -38,6 +257,13 @@ pub trait Pathed {
     fn at_field(self, field_name: &str) -> Self;
     fn at_index(self, index: usize) -> Self;
     fn at_key(self, key_name: &str) -> Self;
+    /// Defaults to recording the variant like a field, via [`Pathed::at_field`]
+    fn at_variant(self, variant_name: &str) -> Self
+    where
+        Self: Sized,
+    {
+        self.at_field(variant_name)
+    }
 }
 
 impl Pathed for &mut MogrificationError {
@@ -61,6 +287,15 @@ impl Pathed for &mut MogrificationError {
         }
         self
     }
+
+    fn at_variant(self, variant_name: &str) -> Self {
+        for err in self.failures.iter_mut() {
+            err.path
+                .parts
+                .push(PathPart::Variant(variant_name.to_string()));
+        }
+        self
+    }
 }
 impl Pathed for MogrificationError {
     fn at_field(mut self, field_name: &str) -> Self {
@@ -77,6 +312,11 @@ impl Pathed for MogrificationError {
         (&mut self).at_key(key_name);
         self
     }
+
+    fn at_variant(mut self, variant_name: &str) -> Self {
+        (&mut self).at_variant(variant_name);
+        self
+    }
 }
 impl<T> Pathed for Result<T, MogrificationError> {
     fn at_field(self, field_name: &str) -> Self {
@@ -99,4 +339,105 @@ impl<T> Pathed for Result<T, MogrificationError> {
             _ => self,
         }
     }
+
+    fn at_variant(self, variant_name: &str) -> Self {
+        match self {
+            Err(err) => Err(err.at_variant(variant_name)),
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{MogrificationError, PathPart, PathTracker, Pathed};
+
+    #[test]
+    fn display() {
+        let err = MogrificationError::new("bad")
+            .at_index(3)
+            .at_key("en")
+            .at_field("tags")
+            .at_variant("Third")
+            .at_field("kind");
+        assert_eq!(err.failures()[0].path(), ".kind::Third.tags[\"en\"][3]");
+    }
+
+    #[test]
+    fn json_pointer() {
+        let err = MogrificationError::new("bad")
+            .at_index(0)
+            .at_key("a/b~c")
+            .at_field("tags");
+        assert_eq!(err.failures()[0].json_pointer(), "/tags/a~1b~0c/0");
+    }
+
+    #[test]
+    fn parse() {
+        let rendered = ".kind::Third.tags[\"en\"][3]";
+        let path: PathTracker = rendered.parse().unwrap();
+        assert_eq!(path.to_string(), rendered);
+
+        let path: PathTracker = "/tags/a~1b~0c/0/01".parse().unwrap();
+        assert_eq!(path.to_string(), ".tags.a/b~c[0].01");
+        assert_eq!(path.json_pointer(), "/tags/a~1b~0c/0/01");
+
+        let escaped = ".a\\.b[\"c\\\"]\"]::d\\[e\\\\";
+        let path: PathTracker = escaped.parse().unwrap();
+        assert_eq!(
+            path.segments().cloned().collect::<Vec<_>>(),
+            vec![
+                PathPart::Field("a.b".to_string()),
+                PathPart::Key("c\"]".to_string()),
+                PathPart::Variant("d[e\\".to_string()),
+            ]
+        );
+        assert_eq!(path.to_string(), escaped);
+
+        assert!("tags".parse::<PathTracker>().is_err());
+        assert!(".tags[\"en".parse::<PathTracker>().is_err());
+        assert!(".tags[x]".parse::<PathTracker>().is_err());
+        assert!("/a~2".parse::<PathTracker>().is_err());
+        assert_eq!("".parse::<PathTracker>().unwrap().to_string(), "");
+    }
+
+    #[test]
+    fn segments() {
+        let err = MogrificationError::new("bad")
+            .at_index(3)
+            .at_key("en")
+            .at_field("tags");
+        let path = err.failures()[0].path_tracker();
+        assert_eq!(
+            path.segments().cloned().collect::<Vec<_>>(),
+            vec![
+                PathPart::Field("tags".to_string()),
+                PathPart::Key("en".to_string()),
+                PathPart::Index(3),
+            ]
+        );
+
+        let parent = path.parent().unwrap();
+        assert_eq!(parent.to_string(), ".tags[\"en\"]");
+        assert!(path.starts_with(&parent));
+        assert!(path.starts_with(&".tags".parse().unwrap()));
+        assert!(path.starts_with(&PathTracker::default()));
+        assert!(!parent.starts_with(path));
+        assert!(!path.starts_with(&".tag".parse().unwrap()));
+        // pointers parse keys as fields, and numeric keys as indexes
+        assert!(path.starts_with(&"/tags/en/3".parse().unwrap()));
+        let numeric = MogrificationError::new("bad").at_key("3").at_variant("Map");
+        assert!(numeric.failures()[0]
+            .path_tracker()
+            .starts_with(&"/Map/3".parse().unwrap()));
+        assert_eq!(PathTracker::default().parent(), None);
+
+        let built = [
+            PathPart::Field("tags".to_string()),
+            PathPart::Key("en".to_string()),
+        ]
+        .into_iter()
+        .collect::<PathTracker>();
+        assert_eq!(built, parent);
+    }
 }
@@ -0,0 +1,113 @@
+//! An RFC 7807 `application/problem+json` view of a [`MogrificationError`], for REST APIs.
+//! With the `axum` or `actix-web` features, errors returned from handlers respond with it as a 422.
+
+use crate::failure::MogrifyFailure;
+use crate::message::MessageCatalog;
+use crate::MogrificationError;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    /// Always `about:blank`, so `title` is the status' reason phrase
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    /// The error's [`MogrificationError::summary`]
+    pub detail: String,
+    /// One per failure
+    pub errors: Vec<ProblemError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProblemError {
+    /// A JSON Pointer into the request body, like `/tags/en/3`
+    pub pointer: String,
+    pub detail: String,
+    pub code: String,
+}
+
+impl ProblemDetails {
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+    pub const STATUS: u16 = 422;
+
+    /// The same document, with each error's `detail` rendered by `catalog`
+    pub fn localized(err: &MogrificationError, catalog: &dyn MessageCatalog) -> ProblemDetails {
+        let mut problem = ProblemDetails::from(err);
+        for (error, failure) in problem.errors.iter_mut().zip(err.failures()) {
+            error.detail = failure.message_in(catalog);
+        }
+        problem
+    }
+}
+
+impl From<&MogrifyFailure> for ProblemError {
+    fn from(failure: &MogrifyFailure) -> Self {
+        ProblemError {
+            pointer: failure.json_pointer(),
+            detail: failure.message().to_string(),
+            code: failure.code().to_string(),
+        }
+    }
+}
+
+impl From<&MogrificationError> for ProblemDetails {
+    fn from(err: &MogrificationError) -> Self {
+        ProblemDetails {
+            problem_type: "about:blank".to_string(),
+            title: "Unprocessable Entity".to_string(),
+            status: ProblemDetails::STATUS,
+            detail: err.summary(),
+            errors: err.failures().iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum_response {
+    use super::ProblemDetails;
+    use crate::MogrificationError;
+    use axum::http::header::CONTENT_TYPE;
+    use axum::http::{HeaderValue, StatusCode};
+    use axum::response::{IntoResponse, Response};
+    use axum::Json;
+
+    impl IntoResponse for ProblemDetails {
+        fn into_response(self) -> Response {
+            let status =
+                StatusCode::from_u16(self.status).unwrap_or(StatusCode::UNPROCESSABLE_ENTITY);
+            // overrides the `application/json` set by `Json`
+            let content_type = [(
+                CONTENT_TYPE,
+                HeaderValue::from_static(ProblemDetails::CONTENT_TYPE),
+            )];
+            (status, content_type, Json(self)).into_response()
+        }
+    }
+
+    impl IntoResponse for MogrificationError {
+        fn into_response(self) -> Response {
+            ProblemDetails::from(&self).into_response()
+        }
+    }
+}
+
+#[cfg(feature = "actix-web")]
+mod actix_response {
+    use super::ProblemDetails;
+    use crate::MogrificationError;
+    use actix_web::http::StatusCode;
+    use actix_web::{HttpResponse, ResponseError};
+
+    impl ResponseError for MogrificationError {
+        fn status_code(&self) -> StatusCode {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+
+        fn error_response(&self) -> HttpResponse {
+            HttpResponse::build(self.status_code())
+                .content_type(ProblemDetails::CONTENT_TYPE)
+                .json(ProblemDetails::from(self))
+        }
+    }
+}
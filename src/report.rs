@@ -0,0 +1,85 @@
+//! A plain, serializable view of a [`MogrificationError`], for API error bodies. The errors
+//! themselves serialize into this shape, and it deserializes back for clients and tests.
+
+use crate::failure::MogrifyFailure;
+use crate::path::PathPart;
+use crate::MogrificationError;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MogrificationReport {
+    pub failures: Vec<FailureReport>,
+    /// Failures left out by the cap, see `MogrificationError::capped`
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub dropped: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailureReport {
+    /// Rendered like `.tags["en"][3]`
+    pub path: String,
+    /// The same path, root first
+    pub segments: Vec<PathPart>,
+    pub message: String,
+    pub code: String,
+    /// The `MessageCatalog` key, for rendering the message in another language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// The params the keyed message is rendered from
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub params: BTreeMap<String, String>,
+    /// `Display` of the underlying error, followed by each of its sources
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub underlying: Vec<String>,
+}
+
+impl From<&MogrifyFailure> for FailureReport {
+    fn from(failure: &MogrifyFailure) -> Self {
+        let mut underlying = Vec::new();
+        let mut source = failure.source();
+        while let Some(err) = source {
+            underlying.push(err.to_string());
+            source = err.source();
+        }
+        FailureReport {
+            path: failure.path(),
+            segments: failure.segments().cloned().collect(),
+            message: failure.message.clone(),
+            code: failure.code.to_string(),
+            key: failure.key.map(str::to_string),
+            params: failure
+                .params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            underlying,
+        }
+    }
+}
+
+impl From<&MogrificationError> for MogrificationReport {
+    fn from(err: &MogrificationError) -> Self {
+        MogrificationReport {
+            failures: err.failures().iter().map(Into::into).collect(),
+            dropped: err.dropped(),
+        }
+    }
+}
+
+impl Serialize for MogrifyFailure {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FailureReport::from(self).serialize(serializer)
+    }
+}
+
+impl Serialize for MogrificationError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MogrificationReport::from(self).serialize(serializer)
+    }
+}
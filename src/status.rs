@@ -0,0 +1,48 @@
+//! Lets gRPC handlers return conversion errors with `?`, keeping every failure's field in a
+//! `google.rpc.BadRequest` detail instead of flattening them into the status message. Failures
+//! left out by the cap are counted in the `dropped` metadata of a `google.rpc.ErrorInfo` detail,
+//! in the `mogrify` domain.
+//!
+//! A keyed failure's `MessageCatalog` key and params are `ErrorInfo` metadata too, named after
+//! its violation, like `field_violations[0].key` and `field_violations[0].params.min`.
+
+use crate::MogrificationError;
+use std::collections::HashMap;
+use tonic::{Code, Status};
+use tonic_types::{ErrorDetails, FieldViolation, StatusExt};
+
+// tells mogrify's metadata apart from a service's own
+const ERROR_INFO_DOMAIN: &str = "mogrify";
+
+impl From<MogrificationError> for Status {
+    fn from(err: MogrificationError) -> Self {
+        let mut metadata = HashMap::new();
+        let violations = err
+            .failures()
+            .iter()
+            .enumerate()
+            .map(|(i, failure)| {
+                let path = failure.path();
+                // BadRequest fields are dot separated from the root, like `tags["en"][3]`
+                let field = path.strip_prefix('.').unwrap_or(&path);
+                // not the violation's `reason`, which tonic-types doesn't encode
+                if let Some(key) = failure.key() {
+                    metadata.insert(format!("field_violations[{i}].key"), key.to_string());
+                }
+                for (name, value) in failure.params().iter() {
+                    let name = format!("field_violations[{i}].params.{name}");
+                    metadata.insert(name, value.to_string());
+                }
+                FieldViolation::new(field, failure.message())
+            })
+            .collect::<Vec<_>>();
+        if err.dropped() > 0 {
+            metadata.insert("dropped".to_string(), err.dropped().to_string());
+        }
+        let mut details = ErrorDetails::with_bad_request(violations);
+        if !metadata.is_empty() {
+            details.set_error_info("MOGRIFICATION_FAILED", ERROR_INFO_DOMAIN, metadata);
+        }
+        Status::with_error_details(Code::InvalidArgument, err.summary(), details)
+    }
+}
@@ -1,20 +1,94 @@
-use crate::{MogrificationError, Pathed};
+use crate::{FailureCode, MessageParams, MogrificationError, Pathed, Warnings};
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
 use std::error::Error;
-use std::hash::Hash;
+use std::future::Future;
+use std::hash::{BuildHasher, Hash};
+use std::pin::Pin;
 use std::str::FromStr;
+use std::task::{Context, Poll};
 
-pub fn capture_error<T, E>(errors: &mut Vec<E>, result: Result<T, E>) -> Option<T> {
+pub use crate::warnings::WarningsMark;
+
+pub fn capture_error<T, E>(errors: &mut impl Extend<E>, result: Result<T, E>) -> Option<T> {
     match result {
         Ok(t) => Some(t),
         Err(err) => {
-            errors.push(err);
+            errors.extend(Some(err));
             None
         }
     }
 }
 
+/// Per-conversion settings the derive passes to the collection helpers. Hand-written conversions
+/// pass one to [`mogrify_seq_in`] or [`mogrify_pairs_in`] to cap their failures.
+#[derive(Debug, Clone, Copy)]
+pub struct Scope<'a> {
+    /// Failures kept per collection, see [`MogrificationError::capped`]
+    pub max_failures: usize,
+    /// Stop at the first failing element
+    pub fail_fast: bool,
+    /// Where the conversion's warnings go, so they can be pathed by element
+    pub warnings: Option<&'a Warnings>,
+}
+
+impl Default for Scope<'_> {
+    fn default() -> Self {
+        Scope {
+            max_failures: usize::MAX,
+            fail_fast: false,
+            warnings: None,
+        }
+    }
+}
+
+/// Collections the helpers can preallocate for their successes
+pub trait WithCapacity {
+    fn with_capacity(capacity: usize) -> Self;
+}
+
+impl<T> WithCapacity for Vec<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+}
+
+impl<T> WithCapacity for VecDeque<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        VecDeque::with_capacity(capacity)
+    }
+}
+
+impl<T> WithCapacity for LinkedList<T> {
+    fn with_capacity(_: usize) -> Self {
+        LinkedList::new()
+    }
+}
+
+impl<T, S: BuildHasher + Default> WithCapacity for HashSet<T, S> {
+    fn with_capacity(capacity: usize) -> Self {
+        HashSet::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<T> WithCapacity for BTreeSet<T> {
+    fn with_capacity(_: usize) -> Self {
+        BTreeSet::new()
+    }
+}
+
+impl<K, V, S: BuildHasher + Default> WithCapacity for HashMap<K, V, S> {
+    fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, S::default())
+    }
+}
+
+impl<K, V> WithCapacity for BTreeMap<K, V> {
+    fn with_capacity(_: usize) -> Self {
+        BTreeMap::new()
+    }
+}
+
 pub fn force_parse<From: AsRef<str>, Into, Err>(source: From) -> Result<Into, Err>
 where
     Err: Any + Error + Send + Sync + 'static,
@@ -23,6 +97,18 @@ where
     source.as_ref().parse()
 }
 
+/// Like [`force_parse`], but failing with [`FailureCode::Parse`]. Used by a bare `parse`.
+pub fn mogrify_parse<From: AsRef<str>, Into, Err>(source: From) -> Result<Into, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: FromStr<Err = Err>,
+{
+    force_parse(source).map_err(|err: Err| {
+        let params = MessageParams::new().with("error", &err);
+        MogrificationError::new_keyed(FailureCode::Parse, "parse", params).with_underlying(err)
+    })
+}
+
 pub fn mogrify_raw<From, Into, Err>(from: From) -> Result<Into, MogrificationError>
 where
     Err: Any + Error + Send + Sync + 'static,
@@ -41,9 +127,63 @@ where
     with(value).map_err(MogrificationError::wrapping)
 }
 
+/// A `parse`, `and_then` or `validate` function that may take the conversion context as a
+/// second argument. `Marker` tells the two signatures apart.
+pub trait WithContext<From, Ctx, Marker> {
+    type Output;
+    fn call_with(&self, value: From, ctx: &Ctx) -> Self::Output;
+}
+
+impl<F, From, Ctx, Out> WithContext<From, Ctx, fn(From) -> Out> for F
+where
+    F: Fn(From) -> Out,
+{
+    type Output = Out;
+    fn call_with(&self, value: From, _: &Ctx) -> Out {
+        self(value)
+    }
+}
+
+impl<F, From, Ctx, Out> WithContext<From, Ctx, fn(From, &Ctx) -> Out> for F
+where
+    F: Fn(From, &Ctx) -> Out,
+{
+    type Output = Out;
+    fn call_with(&self, value: From, ctx: &Ctx) -> Out {
+        self(value, ctx)
+    }
+}
+
+pub fn with_context<F, From, Ctx, Marker>(with: &F, value: From, ctx: &Ctx) -> F::Output
+where
+    F: WithContext<From, Ctx, Marker>,
+{
+    with.call_with(value, ctx)
+}
+
+pub fn mogrify_enumeration<Enumeration, Into, Err>(value: i32) -> Result<Into, MogrificationError>
+where
+    Enumeration: TryFrom<i32>,
+    Err: Any + Error + Send + Sync + 'static,
+    Into: TryFrom<Enumeration, Error = Err>,
+{
+    match Enumeration::try_from(value) {
+        Ok(enumeration) => mogrify_raw(enumeration),
+        Err(_) => Err(MogrificationError::new_keyed(
+            FailureCode::UnknownVariant,
+            "unknown_variant",
+            MessageParams::new().with("value", value),
+        )),
+    }
+}
+
 pub fn mogrify_require<T>(from: Option<T>) -> Result<T, MogrificationError> {
     match from {
-        None => Err(MogrificationError::new("Value is required")),
+        None => Err(MogrificationError::new_keyed(
+            FailureCode::Required,
+            "required",
+            MessageParams::new(),
+        )),
         Some(value) => Ok(value),
     }
 }
@@ -66,6 +206,28 @@ where
     from.map(|value| mogrify_raw_with(with, value)).transpose()
 }
 
+#[allow(clippy::boxed_local)]
+pub fn mogrify_box<From, Into, Err>(from: Box<From>) -> Result<Box<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: TryFrom<From, Error = Err>,
+{
+    mogrify_raw(*from).map(Box::new)
+}
+
+#[allow(clippy::boxed_local)]
+pub fn mogrify_box_with<From, Into, Err>(
+    with: impl Fn(From) -> Result<Into, Err>,
+    from: Box<From>,
+) -> Result<Box<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+{
+    mogrify_raw_with(with, *from).map(Box::new)
+}
+
+// the named collection helpers keep every failure, see `mogrify_seq_in` and `mogrify_pairs_in`
+// for capped ones
 pub fn mogrify_vec<From, Into, Err>(from: Vec<From>) -> Result<Vec<Into>, MogrificationError>
 where
     Err: Any + Error + Send + Sync + 'static,
@@ -80,17 +242,139 @@ pub fn mogrify_vec_with<From, Into, Err>(
 where
     Err: Any + Error + Send + Sync + 'static,
 {
-    let mut errors = Vec::new();
-    let mut successes = Vec::with_capacity(from.len());
+    mogrify_seq_in(Scope::default(), with, from)
+}
+
+pub fn mogrify_vec_deque<From, Into, Err>(
+    from: VecDeque<From>,
+) -> Result<VecDeque<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: TryFrom<From, Error = Err>,
+{
+    mogrify_vec_deque_with(<Into as TryFrom<From>>::try_from, from)
+}
+pub fn mogrify_vec_deque_with<From, Into, Err>(
+    with: impl Fn(From) -> Result<Into, Err>,
+    from: VecDeque<From>,
+) -> Result<VecDeque<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+{
+    mogrify_seq_in(Scope::default(), with, from)
+}
+
+pub fn mogrify_linked_list<From, Into, Err>(
+    from: LinkedList<From>,
+) -> Result<LinkedList<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: TryFrom<From, Error = Err>,
+{
+    mogrify_linked_list_with(<Into as TryFrom<From>>::try_from, from)
+}
+pub fn mogrify_linked_list_with<From, Into, Err>(
+    with: impl Fn(From) -> Result<Into, Err>,
+    from: LinkedList<From>,
+) -> Result<LinkedList<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+{
+    mogrify_seq_in(Scope::default(), with, from)
+}
+
+/// Failing elements are reported at their position in the set's iteration order
+pub fn mogrify_hash_set<From, Into, Err>(
+    from: HashSet<From>,
+) -> Result<HashSet<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: TryFrom<From, Error = Err> + Hash + Eq,
+{
+    mogrify_hash_set_with(<Into as TryFrom<From>>::try_from, from)
+}
+/// Failing elements are reported at their position in the set's iteration order
+pub fn mogrify_hash_set_with<From, Into, Err>(
+    with: impl Fn(From) -> Result<Into, Err>,
+    from: HashSet<From>,
+) -> Result<HashSet<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: Hash + Eq,
+{
+    mogrify_seq_in(Scope::default(), with, from)
+}
+
+/// Failing elements are reported at their position in the set's iteration order
+pub fn mogrify_btree_set<From, Into, Err>(
+    from: BTreeSet<From>,
+) -> Result<BTreeSet<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: TryFrom<From, Error = Err> + Ord,
+{
+    mogrify_btree_set_with(<Into as TryFrom<From>>::try_from, from)
+}
+/// Failing elements are reported at their position in the set's iteration order
+pub fn mogrify_btree_set_with<From, Into, Err>(
+    with: impl Fn(From) -> Result<Into, Err>,
+    from: BTreeSet<From>,
+) -> Result<BTreeSet<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Into: Ord,
+{
+    mogrify_seq_in(Scope::default(), with, from)
+}
+
+/// Converts any sequence or set, stopping at the first failing element
+pub fn mogrify_seq_fail_fast_with<From, Into, Err, Collection>(
+    with: impl Fn(From) -> Result<Into, Err>,
+    from: impl IntoIterator<Item = From>,
+) -> Result<Collection, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Collection: WithCapacity + Extend<Into>,
+{
+    let scope = Scope {
+        fail_fast: true,
+        ..Scope::default()
+    };
+    mogrify_seq_in(scope, with, from)
+}
 
-    for (i, value) in from.into_iter().enumerate() {
-        match mogrify_raw_with(&with, value) {
-            Ok(into) => successes.push(into),
-            Err(err) => errors.push(err.at_index(i)),
+/// Converts any sequence or set, capping or stopping at failures as `scope` says
+// generic over `With` and `Iter` rather than taking `impl Fn`, so the derive can name `Collection`
+pub fn mogrify_seq_in<From, Into, Err, Collection, With, Iter>(
+    scope: Scope<'_>,
+    with: With,
+    from: Iter,
+) -> Result<Collection, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Collection: WithCapacity + Extend<Into>,
+    With: Fn(From) -> Result<Into, Err>,
+    Iter: IntoIterator<Item = From>,
+{
+    let from = from.into_iter();
+    let mut successes = Collection::with_capacity(from.size_hint().0);
+    // collected as we go, so the failure cap bounds memory on huge inputs
+    let mut errors = MogrificationError::capped(scope.max_failures);
+
+    for (i, value) in from.enumerate() {
+        let mark = scope.warnings.map(Warnings::mark);
+        let result = mogrify_raw_with(&with, value);
+        if let Some(mark) = &mark {
+            mark.at_index(i);
+        }
+        match result {
+            Ok(into) => successes.extend(Some(into)),
+            Err(err) if scope.fail_fast => return Err(err.at_index(i)),
+            Err(err) => errors.collect(err.at_index(i)),
         }
     }
 
-    MogrificationError::condense(errors)?;
+    errors.into_result()?;
     Ok(successes)
 }
 
@@ -117,20 +401,286 @@ where
     KeyInto: TryFrom<KeyFrom, Error = KeyErr> + Hash + Eq,
     ValueErr: Any + Error + Send + Sync + 'static,
 {
-    let mut errors = Vec::new();
-    let mut successes = HashMap::new();
+    mogrify_pairs_in(Scope::default(), with, from)
+}
 
-    for (key, value) in from.into_iter() {
+pub fn mogrify_btree_map<KeyFrom, KeyInto, KeyErr, ValueFrom, ValueInto, ValueErr>(
+    from: BTreeMap<KeyFrom, ValueFrom>,
+) -> Result<BTreeMap<KeyInto, ValueInto>, MogrificationError>
+where
+    KeyErr: Any + Error + Send + Sync + 'static,
+    KeyFrom: ToString,
+    KeyInto: TryFrom<KeyFrom, Error = KeyErr> + Ord,
+    ValueErr: Any + Error + Send + Sync + 'static,
+    ValueInto: TryFrom<ValueFrom, Error = ValueErr>,
+{
+    mogrify_btree_map_with(<ValueInto as TryFrom<ValueFrom>>::try_from, from)
+}
+
+pub fn mogrify_btree_map_with<KeyFrom, KeyInto, KeyErr, ValueFrom, ValueInto, ValueErr>(
+    with: impl Fn(ValueFrom) -> Result<ValueInto, ValueErr>,
+    from: BTreeMap<KeyFrom, ValueFrom>,
+) -> Result<BTreeMap<KeyInto, ValueInto>, MogrificationError>
+where
+    KeyErr: Any + Error + Send + Sync + 'static,
+    KeyFrom: ToString,
+    KeyInto: TryFrom<KeyFrom, Error = KeyErr> + Ord,
+    ValueErr: Any + Error + Send + Sync + 'static,
+{
+    mogrify_pairs_in(Scope::default(), with, from)
+}
+
+/// Converts any map, stopping at the first failing key or value
+pub fn mogrify_pairs_fail_fast_with<
+    KeyFrom,
+    KeyInto,
+    KeyErr,
+    ValueFrom,
+    ValueInto,
+    ValueErr,
+    Collection,
+>(
+    with: impl Fn(ValueFrom) -> Result<ValueInto, ValueErr>,
+    from: impl IntoIterator<Item = (KeyFrom, ValueFrom)>,
+) -> Result<Collection, MogrificationError>
+where
+    KeyErr: Any + Error + Send + Sync + 'static,
+    KeyFrom: ToString,
+    KeyInto: TryFrom<KeyFrom, Error = KeyErr>,
+    ValueErr: Any + Error + Send + Sync + 'static,
+    Collection: WithCapacity + Extend<(KeyInto, ValueInto)>,
+{
+    let scope = Scope {
+        fail_fast: true,
+        ..Scope::default()
+    };
+    mogrify_pairs_in(scope, with, from)
+}
+
+/// Converts any map, capping or stopping at failures as `scope` says
+pub fn mogrify_pairs_in<
+    KeyFrom,
+    KeyInto,
+    KeyErr,
+    ValueFrom,
+    ValueInto,
+    ValueErr,
+    Collection,
+    With,
+    Iter,
+>(
+    scope: Scope<'_>,
+    with: With,
+    from: Iter,
+) -> Result<Collection, MogrificationError>
+where
+    KeyErr: Any + Error + Send + Sync + 'static,
+    KeyFrom: ToString,
+    KeyInto: TryFrom<KeyFrom, Error = KeyErr>,
+    ValueErr: Any + Error + Send + Sync + 'static,
+    Collection: WithCapacity + Extend<(KeyInto, ValueInto)>,
+    With: Fn(ValueFrom) -> Result<ValueInto, ValueErr>,
+    Iter: IntoIterator<Item = (KeyFrom, ValueFrom)>,
+{
+    let from = from.into_iter();
+    let mut successes = Collection::with_capacity(from.size_hint().0);
+    let mut errors = MogrificationError::capped(scope.max_failures);
+
+    for (key, value) in from {
         let string_key = key.to_string();
-        let into_key = capture_error(&mut errors, mogrify_raw(key).at_key(&string_key));
+        let mark = scope.warnings.map(Warnings::mark);
+        let into_key = capture_error(
+            &mut errors,
+            mogrify_raw(key)
+                .map_err(|err| err.recode(FailureCode::InvalidKey))
+                .at_key(&string_key),
+        );
+        if scope.fail_fast && !errors.is_empty() {
+            break;
+        }
         let into_value = capture_error(
             &mut errors,
             mogrify_raw_with(&with, value).at_key(&string_key),
         );
+        if let Some(mark) = &mark {
+            mark.at_key(&string_key);
+        }
+        if scope.fail_fast && !errors.is_empty() {
+            break;
+        }
         if let (Some(into_key), Some(into_value)) = (into_key, into_value) {
-            successes.insert(into_key, into_value);
+            successes.extend(Some((into_key, into_value)));
         }
     }
-    MogrificationError::condense(errors)?;
+    errors.into_result()?;
     Ok(successes)
 }
+
+pub async fn mogrify_raw_async<From, Into, Err, Fut>(
+    with: impl Fn(From) -> Fut,
+    value: From,
+) -> Result<Into, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Fut: Future<Output = Result<Into, Err>>,
+{
+    with(value).await.map_err(MogrificationError::wrapping)
+}
+
+pub async fn mogrify_opt_async<From, Into, Err, Fut>(
+    with: impl Fn(From) -> Fut,
+    from: Option<From>,
+) -> Result<Option<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Fut: Future<Output = Result<Into, Err>>,
+{
+    match from {
+        None => Ok(None),
+        Some(value) => mogrify_raw_async(with, value).await.map(Some),
+    }
+}
+
+#[allow(clippy::boxed_local)]
+pub async fn mogrify_box_async<From, Into, Err, Fut>(
+    with: impl Fn(From) -> Fut,
+    from: Box<From>,
+) -> Result<Box<Into>, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Fut: Future<Output = Result<Into, Err>>,
+{
+    mogrify_raw_async(with, *from).await.map(Box::new)
+}
+
+/// Converts any sequence or set, awaiting one element at a time
+pub async fn mogrify_seq_async<From, Into, Err, Fut, Collection, With, Iter>(
+    scope: Scope<'_>,
+    with: With,
+    from: Iter,
+) -> Result<Collection, MogrificationError>
+where
+    Err: Any + Error + Send + Sync + 'static,
+    Fut: Future<Output = Result<Into, Err>>,
+    Collection: WithCapacity + Extend<Into>,
+    With: Fn(From) -> Fut,
+    Iter: IntoIterator<Item = From>,
+{
+    let from = from.into_iter();
+    let mut successes = Collection::with_capacity(from.size_hint().0);
+    let mut errors = MogrificationError::capped(scope.max_failures);
+
+    for (i, value) in from.enumerate() {
+        match mogrify_raw_async(&with, value).await {
+            Ok(into) => successes.extend(Some(into)),
+            Err(err) if scope.fail_fast => return Err(err.at_index(i)),
+            Err(err) => errors.collect(err.at_index(i)),
+        }
+    }
+
+    errors.into_result()?;
+    Ok(successes)
+}
+
+/// Converts any map, awaiting one value at a time
+pub async fn mogrify_pairs_async<
+    KeyFrom,
+    KeyInto,
+    KeyErr,
+    ValueFrom,
+    ValueInto,
+    ValueErr,
+    Fut,
+    Collection,
+    With,
+    Iter,
+>(
+    scope: Scope<'_>,
+    with: With,
+    from: Iter,
+) -> Result<Collection, MogrificationError>
+where
+    KeyErr: Any + Error + Send + Sync + 'static,
+    KeyFrom: ToString,
+    KeyInto: TryFrom<KeyFrom, Error = KeyErr>,
+    ValueErr: Any + Error + Send + Sync + 'static,
+    Fut: Future<Output = Result<ValueInto, ValueErr>>,
+    Collection: WithCapacity + Extend<(KeyInto, ValueInto)>,
+    With: Fn(ValueFrom) -> Fut,
+    Iter: IntoIterator<Item = (KeyFrom, ValueFrom)>,
+{
+    let from = from.into_iter();
+    let mut successes = Collection::with_capacity(from.size_hint().0);
+    let mut errors = MogrificationError::capped(scope.max_failures);
+
+    for (key, value) in from {
+        let string_key = key.to_string();
+        let into_key = capture_error(
+            &mut errors,
+            mogrify_raw(key)
+                .map_err(|err| err.recode(FailureCode::InvalidKey))
+                .at_key(&string_key),
+        );
+        let into_value = capture_error(
+            &mut errors,
+            mogrify_raw_async(&with, value).await.at_key(&string_key),
+        );
+        if let (Some(into_key), Some(into_value)) = (into_key, into_value) {
+            successes.extend(Some((into_key, into_value)));
+        }
+    }
+    errors.into_result()?;
+    Ok(successes)
+}
+
+/// A field conversion that's awaited alongside the others by [`join_pending`]
+pub struct Pending<F: Future> {
+    future: Pin<Box<F>>,
+    output: Option<F::Output>,
+}
+
+impl<F: Future> Pending<F> {
+    pub fn new(future: F) -> Pending<F> {
+        Pending {
+            future: Box::pin(future),
+            output: None,
+        }
+    }
+    pub fn take(&mut self) -> F::Output {
+        self.output
+            .take()
+            .expect("pending conversions are taken once, after join_pending")
+    }
+}
+
+pub trait JoinPending {
+    /// Polls the conversion unless it's already finished, returning whether it has
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> bool;
+}
+
+impl<F: Future> JoinPending for Pending<F> {
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> bool {
+        if self.output.is_none() {
+            if let Poll::Ready(output) = self.future.as_mut().poll(cx) {
+                self.output = Some(output);
+            }
+        }
+        self.output.is_some()
+    }
+}
+
+/// Awaits every conversion concurrently
+pub async fn join_pending(pending: &mut [&mut (dyn JoinPending + Send)]) {
+    std::future::poll_fn(|cx| {
+        let mut finished = true;
+        for pending in pending.iter_mut() {
+            finished &= pending.poll_pending(cx);
+        }
+        if finished {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    })
+    .await
+}
@@ -0,0 +1,172 @@
+//! Checks backing the `len`, `range`, `pattern` and `non_empty` field attributes. Each takes the
+//! converted value and hands it back unchanged if it passes, so they chain like `and_then`.
+
+use crate::{FailureCode, MessageParams, MogrificationError};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque};
+use std::fmt::Display;
+
+/// Anything with a length that `len` and `non_empty` can check
+pub trait Length {
+    fn length(&self) -> usize;
+    /// What's being counted, for failure messages
+    fn unit(&self) -> (&'static str, &'static str) {
+        ("item", "items")
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+    fn unit(&self) -> (&'static str, &'static str) {
+        ("character", "characters")
+    }
+}
+
+impl Length for &str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+    fn unit(&self) -> (&'static str, &'static str) {
+        ("character", "characters")
+    }
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for VecDeque<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for LinkedList<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> Length for HashSet<T, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for BTreeSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V, S> Length for HashMap<K, V, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+pub fn len<T: Length>(
+    value: T,
+    min: Option<usize>,
+    max: Option<usize>,
+) -> Result<T, MogrificationError> {
+    let length = value.length();
+    let (unit, units) = value.unit();
+    let params = MessageParams::new()
+        .with("actual", length)
+        .with("unit", unit)
+        .with("units", units);
+    let (key, params) = match (min, max) {
+        (Some(min), Some(max)) if length < min || length > max => {
+            ("length.between", params.with("min", min).with("max", max))
+        }
+        (Some(min), None) if length < min => ("length.min", params.with("min", min)),
+        (None, Some(max)) if length > max => ("length.max", params.with("max", max)),
+        _ => return Ok(value),
+    };
+    Err(MogrificationError::new_keyed(
+        FailureCode::Length,
+        key,
+        params,
+    ))
+}
+
+pub fn non_empty<T: Length>(value: T) -> Result<T, MogrificationError> {
+    if value.length() == 0 {
+        Err(MogrificationError::new_keyed(
+            FailureCode::Empty,
+            "empty",
+            MessageParams::new(),
+        ))
+    } else {
+        Ok(value)
+    }
+}
+
+pub fn range<T: PartialOrd + Display>(
+    value: T,
+    min: Option<T>,
+    max: Option<T>,
+) -> Result<T, MogrificationError> {
+    let (key, params) = match (min, max) {
+        (Some(min), Some(max)) if value < min || value > max => (
+            "range.between",
+            MessageParams::new().with("min", min).with("max", max),
+        ),
+        (Some(min), None) if value < min => ("range.min", MessageParams::new().with("min", min)),
+        (None, Some(max)) if value > max => ("range.max", MessageParams::new().with("max", max)),
+        _ => return Ok(value),
+    };
+    Err(MogrificationError::new_keyed(
+        FailureCode::OutOfRange,
+        key,
+        params.with("actual", value),
+    ))
+}
+
+/// A regex that's compiled the first time it's used, so it can live in a `static`
+#[cfg(feature = "regex")]
+pub struct Pattern {
+    source: &'static str,
+    regex: std::sync::OnceLock<regex::Regex>,
+}
+
+#[cfg(feature = "regex")]
+impl Pattern {
+    pub const fn new(source: &'static str) -> Pattern {
+        Pattern {
+            source,
+            regex: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Panics if the pattern isn't a valid regex. The derive rejects those at compile time.
+    pub fn regex(&self) -> &regex::Regex {
+        self.regex.get_or_init(|| {
+            regex::Regex::new(self.source)
+                .unwrap_or_else(|err| panic!("invalid mogrify pattern {:?}: {err}", self.source))
+        })
+    }
+}
+
+#[cfg(feature = "regex")]
+pub fn pattern<S: AsRef<str>>(value: S, pattern: &Pattern) -> Result<S, MogrificationError> {
+    if pattern.regex().is_match(value.as_ref()) {
+        Ok(value)
+    } else {
+        Err(MogrificationError::new_keyed(
+            FailureCode::Pattern,
+            "pattern",
+            MessageParams::new().with("pattern", pattern.source),
+        ))
+    }
+}
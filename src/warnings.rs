@@ -0,0 +1,149 @@
+use crate::context::TryFromWith;
+use crate::failure::MogrifyFailure;
+use crate::path::{PathPart, Pathed};
+use crate::MogrificationError;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Collects anything suspicious but acceptable found during a conversion. Passed as the context of
+/// conversions derived with `#[mogrify(Source, warnings)]`, or reached through `AsRef<Warnings>`
+/// on a custom `context = ...`.
+#[derive(Debug, Default)]
+pub struct Warnings {
+    // behind a mutex, so a `&Warnings` can be shared with other threads
+    warnings: Mutex<Vec<MogrifyFailure>>,
+}
+
+impl Warnings {
+    pub fn new() -> Warnings {
+        Warnings::default()
+    }
+
+    /// Raises a warning from inside a conversion
+    pub fn warn(&self, warning: MogrificationError) {
+        self.lock().extend(warning.failures);
+    }
+
+    /// Marks where a step of a conversion starts, so the warnings raised since can be pathed like
+    /// errors
+    pub fn mark(&self) -> WarningsMark<'_> {
+        WarningsMark {
+            warnings: self,
+            start: self.lock().len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn into_inner(self) -> Vec<MogrifyFailure> {
+        self.warnings
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // a panicking conversion can't leave the warnings half written, so poisoning is ignored
+    fn lock(&self) -> MutexGuard<'_, Vec<MogrifyFailure>> {
+        self.warnings.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl AsRef<Warnings> for Warnings {
+    fn as_ref(&self) -> &Warnings {
+        self
+    }
+}
+
+/// A converted value, or the error, with the warnings raised along the way
+#[derive(Debug)]
+pub struct Mogrified<T> {
+    pub value: T,
+    pub warnings: Vec<MogrifyFailure>,
+}
+
+impl<T> Mogrified<T> {
+    /// Promotes any warnings to failures
+    pub fn strict(self) -> Result<T, MogrificationError> {
+        if self.warnings.is_empty() {
+            Ok(self.value)
+        } else {
+            Err(MogrificationError {
+                failures: self.warnings,
+                ..MogrificationError::empty()
+            })
+        }
+    }
+}
+
+impl Mogrified<MogrificationError> {
+    /// The error, with any warnings promoted to failures after its own
+    pub fn strict_error(mut self) -> MogrificationError {
+        self.value.failures.append(&mut self.warnings);
+        self.value
+    }
+}
+
+/// Converts while collecting warnings. Implemented for every conversion derived with
+/// `#[mogrify(Source, warnings)]`.
+pub trait TryFromWarned<Source>: Sized {
+    fn try_from_warned(source: Source) -> Result<Mogrified<Self>, Mogrified<MogrificationError>>;
+
+    /// Fails on warnings too
+    fn try_from_strict(source: Source) -> Result<Self, MogrificationError> {
+        match Self::try_from_warned(source) {
+            Ok(warned) => warned.strict(),
+            Err(warned) => Err(warned.strict_error()),
+        }
+    }
+}
+
+impl<Source, T> TryFromWarned<Source> for T
+where
+    T: TryFromWith<Source, Warnings>,
+{
+    fn try_from_warned(source: Source) -> Result<Mogrified<Self>, Mogrified<MogrificationError>> {
+        let warnings = Warnings::new();
+        let result = T::try_from_with(source, &warnings);
+        let warnings = warnings.into_inner();
+        match result {
+            Ok(value) => Ok(Mogrified { value, warnings }),
+            Err(value) => Err(Mogrified { value, warnings }),
+        }
+    }
+}
+
+/// Where a step of a conversion started, see [`Warnings::mark`]
+pub struct WarningsMark<'a> {
+    warnings: &'a Warnings,
+    start: usize,
+}
+
+impl WarningsMark<'_> {
+    fn push(&self, part: impl Fn() -> PathPart) {
+        for warning in self.warnings.lock().iter_mut().skip(self.start) {
+            warning.path.parts.push(part());
+        }
+    }
+}
+
+impl Pathed for &WarningsMark<'_> {
+    fn at_field(self, field_name: &str) -> Self {
+        self.push(|| PathPart::Field(field_name.to_string()));
+        self
+    }
+
+    fn at_index(self, index: usize) -> Self {
+        self.push(|| PathPart::Index(index));
+        self
+    }
+
+    fn at_key(self, key_name: &str) -> Self {
+        self.push(|| PathPart::Key(key_name.to_string()));
+        self
+    }
+
+    fn at_variant(self, variant_name: &str) -> Self {
+        self.push(|| PathPart::Variant(variant_name.to_string()));
+        self
+    }
+}